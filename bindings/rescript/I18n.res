@@ -64,6 +64,8 @@ module I18n = {
 
   @send external setLocale: (t, string) => string = "setLocale"
   @send external getLocale: t => string = "getLocale"
+  // JSON [{code, field, locale, message}] of problems found in the config
+  @send external getConfigWarnings: t => string = "getConfigWarnings"
   @send external getLocales: t => array<string> = "getLocales"
 
   @send external getCatalog: t => Js.Dict.t<'a> = "getCatalog"
@@ -80,6 +82,38 @@ module I18n = {
   @send external init: (t, req, res, next) => unit = "init"
 }
 
+// WASM engine (i18n-wasm), for the catalog snapshot shared between workers
+module I18nWasm = {
+  type t
+
+  // serializeState/shareState output; shareState backs it with a
+  // SharedArrayBuffer when the host has one, so postMessage shares it
+  type state = Js.TypedArray2.Uint8Array.t
+
+  @module("i18n-wasm") @new
+  external make: string => t = "I18nWasm"
+
+  @module("i18n-wasm") @scope("I18nWasm")
+  external fromState: state => t = "fromState"
+
+  @send external loadCatalog: (t, string, string) => unit = "loadCatalog"
+  @send external serializeState: t => state = "serializeState"
+  @send external shareState: t => state = "shareState"
+
+  @send external translate: (t, string) => string = "translate"
+  @send external translatePlural: (t, string, float) => string = "translatePlural"
+  @send external setLocale: (t, string) => string = "setLocale"
+  @send external getLocale: t => string = "getLocale"
+  // JSON [{code, field, locale, message}] of problems found in the config
+  @send external getConfigWarnings: t => string = "getConfigWarnings"
+
+  // Thrown errors carry a stable code ("INVALID_CATALOG", "MISSING_KEY",
+  // ...) and, for catalogs and JSON arguments, the 1-based line and column
+  @get external errorCode: Js.Exn.t => string = "code"
+  @get @return(nullable) external errorLine: Js.Exn.t => option<int> = "line"
+  @get @return(nullable) external errorColumn: Js.Exn.t => option<int> = "column"
+}
+
 // Usage example module
 module Example = {
   let config = I18nConfig.make(
@@ -0,0 +1,337 @@
+// Build script: compile CLDR plural rules into static lookup tables
+//
+// Reads the cldr-json supplemental plural data in data/cldr/ and emits
+// $OUT_DIR/plural_rules.rs, which plural.rs includes. Each distinct rule set is
+// emitted once (with its @integer/@decimal samples in a parallel table) and
+// shared by every locale that uses it.
+//
+// It also emits $OUT_DIR/relative_time.rs from data/relative_time.json, the
+// strings relative_time.rs serves to RelativeTimeFormat.
+//
+// Without the default `all-locales` feature only the languages picked by the
+// `locales-*` group features and the I18N_WASM_LOCALES list (comma-separated
+// language codes) are compiled in, plus the en/root defaults; this trims both
+// outputs.
+use std::collections::{BTreeMap, BTreeSet};
+use std::env;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+const CATEGORIES: [(&str, &str); 6] = [
+    ("zero", "Zero"),
+    ("one", "One"),
+    ("two", "Two"),
+    ("few", "Few"),
+    ("many", "Many"),
+    ("other", "Other"),
+];
+
+/// Language groups behind the `locales-*` cargo features
+const LOCALE_GROUPS: [(&str, &[&str]); 7] = [
+    (
+        "LOCALES_EUROPEAN",
+        &[
+            "an", "ast", "be", "bg", "br", "bs", "ca", "ce", "cs", "cy", "da", "de", "dsb", "el",
+            "en", "eo", "es", "et", "eu", "fi", "fo", "fr", "fur", "fy", "ga", "gd", "gl", "gsw",
+            "gv", "hr", "hsb", "hu", "hy", "ia", "io", "is", "it", "jbo", "ji", "ka", "ksh", "kw",
+            "lb", "lij", "lt", "lv", "mk", "mo", "mt", "nb", "nl", "nn", "no", "os", "pl", "prg",
+            "pt", "rm", "ro", "ru", "sc", "scn", "se", "sh", "sk", "sl", "sma", "smi", "smj", "smn",
+            "sms", "sq", "sr", "sv", "uk", "vec", "vo", "wa", "wae", "yi",
+        ],
+    ),
+    ("LOCALES_CJK", &["ii", "ja", "ko", "yue", "zh"]),
+    (
+        "LOCALES_SOUTH_ASIAN",
+        &[
+            "as", "bal", "bho", "bn", "bo", "brx", "doi", "dv", "dz", "gu", "hi", "kn", "ks", "ml",
+            "mr", "ne", "or", "pa", "sat", "sd", "si", "ta", "te", "ur",
+        ],
+    ),
+    (
+        "LOCALES_ASIA_PACIFIC",
+        &[
+            "ceb", "fil", "haw", "hnj", "id", "in", "jv", "jw", "km", "lo", "ms", "my", "su", "th",
+            "tl", "to", "tpi", "vi",
+        ],
+    ),
+    (
+        "LOCALES_MIDDLE_EAST",
+        &[
+            "ar", "ars", "az", "ckb", "fa", "he", "iw", "kk", "ku", "ky", "mn", "ps", "sah", "sdh",
+            "syr", "tk", "tr", "ug", "uz",
+        ],
+    ),
+    (
+        "LOCALES_AFRICAN",
+        &[
+            "af", "ak", "am", "asa", "bem", "bez", "bm", "cgg", "ee", "ff", "guw", "ha", "ig",
+            "jgo", "jmc", "kab", "kaj", "kcg", "kde", "kea", "kkj", "kl", "ksb", "lag", "lg", "ln", "mas",
+            "mg", "mgo", "naq", "nd", "nnh", "nqo", "nr", "nso", "ny", "nyn", "om", "pcm", "rof",
+            "rwk", "saq", "seh", "ses", "sg", "shi", "sn", "so", "ss", "ssy", "st", "sw", "teo",
+            "ti", "tig", "tn", "ts", "tzm", "ve", "vun", "wo", "xh", "xog", "yo", "zu",
+        ],
+    ),
+    ("LOCALES_AMERICAS", &["chr", "iu", "lkt", "nah", "osa", "pap"]),
+];
+
+fn main() {
+    let data_dir = Path::new("data/cldr");
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=data/cldr/plurals.json");
+    println!("cargo:rerun-if-changed=data/cldr/ordinals.json");
+    println!("cargo:rerun-if-changed=data/relative_time.json");
+    println!("cargo:rerun-if-env-changed=I18N_WASM_LOCALES");
+
+    let selected = selected_languages();
+
+    let mut rule_sets: Vec<(String, String)> = Vec::new();
+    let mut cldr_version: Option<String> = None;
+    let mut out = String::new();
+    out.push_str("// @generated by build.rs from data/cldr - do not edit\n\n");
+
+    for (file, key, table) in [
+        ("plurals.json", "plurals-type-cardinal", "CARDINAL_LOCALES"),
+        ("ordinals.json", "plurals-type-ordinal", "ORDINAL_LOCALES"),
+    ] {
+        let (version, mut locales) = read_rules(&data_dir.join(file), key);
+        if let Some(selected) = &selected {
+            locales.retain(|locale, _| {
+                let language = locale.split('-').next().unwrap();
+                selected.contains(language)
+            });
+        }
+        match &cldr_version {
+            Some(existing) if *existing != version => {
+                panic!("{} is CLDR {}, expected {}", file, version, existing)
+            }
+            _ => cldr_version = Some(version),
+        }
+        writeln!(out, "pub(crate) static {}: &[(&str, u16)] = &[", table).unwrap();
+        for (locale, rules) in &locales {
+            let code = (rule_set_code(rules), samples_code(rules));
+            let index = match rule_sets.iter().position(|existing| *existing == code) {
+                Some(index) => index,
+                None => {
+                    rule_sets.push(code);
+                    rule_sets.len() - 1
+                }
+            };
+            writeln!(out, "    ({:?}, {}),", locale, index).unwrap();
+        }
+        out.push_str("];\n\n");
+    }
+
+    writeln!(out, "pub(crate) static CLDR_VERSION: &str = {:?};\n", cldr_version.unwrap()).unwrap();
+
+    out.push_str("pub(crate) static RULE_SETS: &[RuleSet] = &[\n");
+    for (rules, _) in &rule_sets {
+        writeln!(out, "    {},", rules).unwrap();
+    }
+    out.push_str("];\n\n");
+
+    out.push_str("pub(crate) static SAMPLE_SETS: &[&[(PluralCategory, &str)]] = &[\n");
+    for (_, samples) in &rule_sets {
+        writeln!(out, "    {},", samples).unwrap();
+    }
+    out.push_str("];\n");
+
+    let out_path = Path::new(&env::var("OUT_DIR").unwrap()).join("plural_rules.rs");
+    fs::write(out_path, out).unwrap();
+
+    write_relative_time(Path::new("data/relative_time.json"), selected.as_ref());
+}
+
+/// Units relative time data names, and the ones with words for one back and
+/// ahead ("yesterday")
+const RELATIVE_TIME_UNITS: [&str; 7] = ["second", "minute", "hour", "day", "week", "month", "year"];
+const RELATIVE_TIME_WORDS: [&str; 4] = ["day", "week", "month", "year"];
+
+/// Emit the relative time strings of the selected languages as
+/// `RelativeTimeData` entries, sorted by language, with the data's source and
+/// which languages have every string (full) or only some (partial)
+fn write_relative_time(path: &Path, selected: Option<&BTreeSet<String>>) {
+    let text = fs::read_to_string(path)
+        .unwrap_or_else(|e| panic!("cannot read {}: {}", path.display(), e));
+    let json: serde_json::Value = serde_json::from_str(&text)
+        .unwrap_or_else(|e| panic!("invalid JSON in {}: {}", path.display(), e));
+    let locales = json["locales"]
+        .as_object()
+        .unwrap_or_else(|| panic!("{} has no locales table", path.display()));
+    let strings = |value: &serde_json::Value| -> Vec<String> {
+        let pair = value.as_array().unwrap_or_else(|| panic!("{}: expected a pair, got {}", path.display(), value));
+        pair.iter().map(|item| item.as_str().unwrap().to_string()).collect()
+    };
+    let optional = |value: &serde_json::Value| match value.as_str() {
+        Some(text) => format!("Some({:?})", text),
+        None => "None".to_string(),
+    };
+
+    let mut out = String::new();
+    out.push_str("// @generated by build.rs from data/relative_time.json - do not edit\n\n");
+    out.push_str("pub(crate) static LOCALES: &[RelativeTimeData] = &[\n");
+    let languages: BTreeMap<&String, &serde_json::Value> = locales.iter().collect();
+    let (mut full, mut partial) = (Vec::new(), Vec::new());
+    for (language, data) in languages {
+        if selected.is_some_and(|selected| !selected.contains(language.as_str())) {
+            continue;
+        }
+        let complete = ["now", "past", "future"].iter().all(|key| data[key].is_string())
+            && RELATIVE_TIME_WORDS.iter().all(|unit| data["relative"][unit].is_array())
+            && ["long", "short", "narrow"]
+                .iter()
+                .all(|style| RELATIVE_TIME_UNITS.iter().all(|unit| data["units"][style][unit].is_array()));
+        if complete { &mut full } else { &mut partial }.push(language.as_str());
+        writeln!(out, "    RelativeTimeData {{").unwrap();
+        writeln!(out, "        language: {:?},", language).unwrap();
+        writeln!(out, "        now: {},", optional(&data["now"])).unwrap();
+        writeln!(out, "        past: {},", optional(&data["past"])).unwrap();
+        writeln!(out, "        future: {},", optional(&data["future"])).unwrap();
+        out.push_str("        relative: &[");
+        for (unit, names) in data["relative"].as_object().into_iter().flatten() {
+            let names = strings(names);
+            write!(out, "({:?}, {:?}, {:?}), ", unit, names[0], names[1]).unwrap();
+        }
+        out.push_str("],\n        units: &[");
+        for (style, units) in data["units"].as_object().into_iter().flatten() {
+            for (unit, forms) in units.as_object().unwrap() {
+                let forms = strings(forms);
+                write!(out, "({:?}, {:?}, {:?}, {:?}), ", style, unit, forms[0], forms[1]).unwrap();
+            }
+        }
+        out.push_str("],\n    },\n");
+    }
+    out.push_str("];\n\n");
+
+    let source = json["source"].as_str().unwrap_or_else(|| panic!("{} has no source", path.display()));
+    writeln!(out, "pub(crate) const SOURCE: &str = {:?};", source).unwrap();
+    writeln!(out, "pub(crate) const FULL_LOCALES: &[&str] = &{:?};", full).unwrap();
+    writeln!(out, "pub(crate) const PARTIAL_LOCALES: &[&str] = &{:?};", partial).unwrap();
+
+    let out_path = Path::new(&env::var("OUT_DIR").unwrap()).join("relative_time.rs");
+    fs::write(out_path, out).unwrap();
+}
+
+/// Languages to compile in, or None for every CLDR locale
+fn selected_languages() -> Option<BTreeSet<String>> {
+    if env::var_os("CARGO_FEATURE_ALL_LOCALES").is_some() {
+        return None;
+    }
+
+    // en and root back the cardinal and ordinal defaults
+    let mut selected: BTreeSet<String> = ["en", "root"].iter().map(|s| s.to_string()).collect();
+    for (feature, languages) in LOCALE_GROUPS {
+        if env::var_os(format!("CARGO_FEATURE_{}", feature)).is_some() {
+            selected.extend(languages.iter().map(|s| s.to_string()));
+        }
+    }
+    if let Ok(list) = env::var("I18N_WASM_LOCALES") {
+        let languages = list.split(',').map(|s| s.trim().to_lowercase()).filter(|s| !s.is_empty());
+        selected.extend(languages);
+    }
+    Some(selected)
+}
+
+/// Load the CLDR release and `{locale: {category: rule}}`, keyed by lowercase locale id
+fn read_rules(path: &Path, key: &str) -> (String, BTreeMap<String, BTreeMap<String, String>>) {
+    let text = fs::read_to_string(path)
+        .unwrap_or_else(|e| panic!("cannot read {}: {}", path.display(), e));
+    let json: serde_json::Value = serde_json::from_str(&text)
+        .unwrap_or_else(|e| panic!("invalid JSON in {}: {}", path.display(), e));
+
+    let version = json["supplemental"]["version"]["_cldrVersion"]
+        .as_str()
+        .unwrap_or_else(|| panic!("{} has no _cldrVersion", path.display()))
+        .to_string();
+
+    let mut locales = BTreeMap::new();
+    let table = json["supplemental"][key]
+        .as_object()
+        .unwrap_or_else(|| panic!("{} has no {} table", path.display(), key));
+
+    for (locale, rules) in table {
+        let mut categories = BTreeMap::new();
+        for (name, rule) in rules.as_object().unwrap() {
+            let category = name.trim_start_matches("pluralRule-count-").to_string();
+            categories.insert(category, rule.as_str().unwrap().to_string());
+        }
+        locales.insert(locale.to_lowercase(), categories);
+    }
+
+    (version, locales)
+}
+
+/// Rust source for one rule set: `&[(PluralCategory::One, &[&[Relation, ..], ..]), ..]`
+fn rule_set_code(rules: &BTreeMap<String, String>) -> String {
+    let mut code = String::from("&[");
+    for (name, variant) in &CATEGORIES[..5] {
+        let Some(rule) = rules.get(*name) else { continue };
+        let condition = rule.split('@').next().unwrap().trim();
+        if condition.is_empty() {
+            continue;
+        }
+
+        write!(code, "(PluralCategory::{}, &[", variant).unwrap();
+        for and_chain in condition.split(" or ") {
+            code.push_str("&[");
+            for relation in and_chain.split(" and ") {
+                code.push_str(&relation_code(relation.trim()));
+                code.push_str(", ");
+            }
+            code.push_str("], ");
+        }
+        code.push_str("]), ");
+    }
+    code.push(']');
+    code
+}
+
+/// Rust source for the raw sample text per category: `&[(PluralCategory::One, "@integer 1"), ..]`
+fn samples_code(rules: &BTreeMap<String, String>) -> String {
+    let mut code = String::from("&[");
+    for (name, variant) in CATEGORIES {
+        let Some(rule) = rules.get(name) else { continue };
+        let samples = rule.find('@').map(|at| rule[at..].trim()).unwrap_or("");
+        write!(code, "(PluralCategory::{}, {:?}), ", variant, samples).unwrap();
+    }
+    code.push(']');
+    code
+}
+
+/// Rust source for a single relation such as `i % 100 != 11..19,40`
+fn relation_code(relation: &str) -> String {
+    let (lhs, negated, rhs) = if let Some((lhs, rhs)) = relation.split_once("!=") {
+        (lhs, true, rhs)
+    } else if let Some((lhs, rhs)) = relation.split_once('=') {
+        (lhs, false, rhs)
+    } else {
+        panic!("unsupported plural relation: {}", relation);
+    };
+
+    let (operand, modulus) = match lhs.split_once('%') {
+        Some((operand, modulus)) => (operand.trim(), modulus.trim().parse::<u64>().unwrap()),
+        None => (lhs.trim(), 0),
+    };
+    let operand = match operand {
+        "n" => "N",
+        "i" => "I",
+        "v" => "V",
+        "w" => "W",
+        "f" => "F",
+        "t" => "T",
+        "e" | "c" => "E",
+        other => panic!("unsupported plural operand: {}", other),
+    };
+
+    let mut ranges = String::new();
+    for item in rhs.split(',') {
+        let (low, high) = item.trim().split_once("..").unwrap_or((item.trim(), item.trim()));
+        write!(ranges, "({}, {}), ", low.parse::<u64>().unwrap(), high.parse::<u64>().unwrap())
+            .unwrap();
+    }
+
+    format!(
+        "Relation {{ operand: Operand::{}, modulus: {}, negated: {}, ranges: &[{}] }}",
+        operand, modulus, negated, ranges
+    )
+}
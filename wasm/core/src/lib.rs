@@ -0,0 +1,76 @@
+// i18n-core - the plural engine, locale handling and relative time data
+// behind i18n-wasm
+//
+// Plain Rust with no JS dependency, for server-side use and for the other
+// bindings. i18n-wasm wraps these types for JavaScript; the optional
+// `wasm-bindgen` feature lets it export the shared enums as they are.
+mod locale;
+mod plural;
+mod relative_time;
+
+pub use locale::{canonicalize_locale, is_well_formed_locale};
+pub use plural::{
+    evaluate_rule_set, supported_locales, Operand, PluralCategory, PluralOperands, PluralRuleType, PluralRules,
+    Relation, RuleSet, CLDR_VERSION,
+};
+pub use relative_time::{
+    relative_time_data, RelativeTimeData, RELATIVE_TIME_FULL_LOCALES, RELATIVE_TIME_PARTIAL_LOCALES,
+    RELATIVE_TIME_SOURCE,
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_canonicalize_locale() {
+        assert_eq!(canonicalize_locale("EN_us"), "en-US");
+        assert_eq!(canonicalize_locale("zh-hant-tw"), "zh-Hant-TW");
+        assert_eq!(canonicalize_locale("iw"), "he");
+
+        for tag in ["en", "en_US", "zh-Hant-TW", "es-419", "de-CH-1996", "sl-rozaj-biske", "th-TH-u-nu-thai", "x-pig-latin"] {
+            assert!(is_well_formed_locale(tag), "{}", tag);
+        }
+        for tag in ["", "e", "en-toolongvariant", "en--US", "en-US-", "en-u", "1en", "en-x", "en-a-bc-x"] {
+            assert!(!is_well_formed_locale(tag), "{}", tag);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "all-locales")]
+    fn test_select_without_bindings() {
+        let ru = PluralRules::new("ru-RU");
+        assert_eq!(ru.select(1.0), PluralCategory::One);
+        assert_eq!(ru.select(3.0), PluralCategory::Few);
+        assert_eq!(ru.select(5.0), PluralCategory::Many);
+        assert_eq!(ru.select_decimal("1.5"), Some(PluralCategory::Other));
+        assert_eq!(ru.select_decimal("1.x"), None);
+        assert_eq!(ru.resolved_locale(PluralRuleType::Cardinal), Some("ru"));
+
+        let en = PluralRules::with_type("en", PluralRuleType::Ordinal);
+        assert_eq!(en.select_integer(22), PluralCategory::Two);
+        assert_eq!(en.categories(PluralRuleType::Ordinal).len(), 4);
+    }
+
+    #[test]
+    #[cfg(feature = "all-locales")]
+    fn test_relative_time_data() {
+        let en = relative_time_data("en").unwrap();
+        assert_eq!(en.past, Some("{0} {1} ago"));
+        assert!(en.units.contains(&("long", "day", "day", "days")));
+        assert_eq!(relative_time_data("de").unwrap().relative, &[("day", "gestern", "morgen")]);
+        assert!(relative_time_data("xx").is_none());
+        assert_eq!(RELATIVE_TIME_FULL_LOCALES, ["en"]);
+        assert!(RELATIVE_TIME_PARTIAL_LOCALES.contains(&"ja"));
+    }
+
+    #[test]
+    #[cfg(feature = "all-locales")]
+    fn test_locales_without_data() {
+        let unknown = PluralRules::new("xx");
+        assert!(!unknown.has_rules(PluralRuleType::Cardinal));
+        assert_eq!(unknown.resolved_locale(PluralRuleType::Cardinal), Some("en"));
+        assert!(supported_locales(PluralRuleType::Cardinal).any(|locale| locale == "pt-pt"));
+        assert!(supported_locales(PluralRuleType::Ordinal).all(|locale| locale != "root"));
+    }
+}
@@ -0,0 +1,94 @@
+// Locale tags - BCP 47 canonicalization shared by every locale-aware API
+
+/// Deprecated ISO 639 language codes and their replacements
+const LEGACY_LANGUAGES: &[(&str, &str)] = &[
+    ("in", "id"),
+    ("iw", "he"),
+    ("ji", "yi"),
+    ("jw", "jv"),
+    ("mo", "ro"),
+    ("no", "nb"),
+    ("tl", "fil"),
+];
+
+/// Canonicalize a locale tag: "_" becomes "-", legacy language codes are
+/// replaced (iw -> he) and subtags get BCP 47 casing, so "EN_us" -> "en-US"
+/// and "zh-hant-tw" -> "zh-Hant-TW". Shared by every locale-aware API.
+pub fn canonicalize_locale(tag: &str) -> String {
+    let mut in_extension = false;
+    tag.trim()
+        .split(['-', '_'])
+        .filter(|part| !part.is_empty())
+        .enumerate()
+        .map(|(index, part)| {
+            let part = part.to_lowercase();
+            if index == 0 {
+                return LEGACY_LANGUAGES
+                    .iter()
+                    .find(|(legacy, _)| *legacy == part)
+                    .map(|(_, modern)| modern.to_string())
+                    .unwrap_or(part);
+            }
+            // Everything after a singleton (-u-, -x-) is left lowercase
+            in_extension |= part.len() == 1;
+            let alphabetic = part.chars().all(|c| c.is_ascii_alphabetic());
+            match part.len() {
+                _ if in_extension => part,
+                2 if alphabetic => part.to_uppercase(),
+                4 if alphabetic => part[..1].to_uppercase() + &part[1..],
+                _ => part,
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Whether a tag is well-formed BCP 47: language, then optional script,
+/// region, variants, extensions (-u-ca-buddhist) and private use (-x-...).
+/// "_" is accepted as a separator, as everywhere else; grandfathered tags
+/// such as "i-klingon" are not.
+pub fn is_well_formed_locale(tag: &str) -> bool {
+    let alphanumeric = |part: &str, min: usize, max: usize| {
+        (min..=max).contains(&part.len()) && part.chars().all(|c| c.is_ascii_alphanumeric())
+    };
+    let alphabetic = |part: &str| part.chars().all(|c| c.is_ascii_alphabetic());
+
+    let mut parts = tag.trim().split(['-', '_']).peekable();
+    let Some(language) = parts.next() else {
+        return false;
+    };
+    if language.eq_ignore_ascii_case("x") {
+        return parts.peek().is_some() && parts.all(|part| alphanumeric(part, 1, 8));
+    }
+    if !alphabetic(language) || !matches!(language.len(), 2 | 3 | 5..=8) {
+        return false;
+    }
+    let script = |part: &&str| part.len() == 4 && alphabetic(part);
+    let region = |part: &&str| {
+        (part.len() == 2 && alphabetic(part)) || (part.len() == 3 && part.chars().all(|c| c.is_ascii_digit()))
+    };
+    let variant = |part: &&str| {
+        alphanumeric(part, 5, 8) || (part.len() == 4 && part.starts_with(|c: char| c.is_ascii_digit()) && alphanumeric(part, 4, 4))
+    };
+    parts.next_if(script);
+    parts.next_if(region);
+    while parts.next_if(variant).is_some() {}
+
+    while let Some(singleton) = parts.next() {
+        if singleton.len() != 1 || !alphanumeric(singleton, 1, 1) {
+            return false;
+        }
+        let (min, max) = if singleton.eq_ignore_ascii_case("x") { (1, 8) } else { (2, 8) };
+        let mut subtags = 0;
+        while parts.next_if(|part| alphanumeric(part, min, max)).is_some() {
+            subtags += 1;
+        }
+        if subtags == 0 {
+            return false;
+        }
+        if singleton.eq_ignore_ascii_case("x") {
+            return parts.next().is_none();
+        }
+    }
+    true
+}
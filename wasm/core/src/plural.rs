@@ -0,0 +1,429 @@
+// CLDR plural rules - category selection for cardinals and ordinals
+//
+// Rules for every CLDR locale are compiled into static tables by build.rs.
+// A locale resolves region-first (pt-PT before pt) and locales without data
+// fall back to English cardinals and the root ordinals.
+use serde::{Deserialize, Serialize};
+
+use super::canonicalize_locale;
+
+/// Unicode CLDR Plural Categories
+/// See: https://unicode.org/reports/tr35/tr35-numbers.html#Language_Plural_Rules
+#[cfg_attr(feature = "wasm-bindgen", wasm_bindgen::prelude::wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PluralCategory {
+    Zero,
+    One,
+    Two,
+    Few,
+    Many,
+    Other,
+}
+
+impl PluralCategory {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PluralCategory::Zero => "zero",
+            PluralCategory::One => "one",
+            PluralCategory::Two => "two",
+            PluralCategory::Few => "few",
+            PluralCategory::Many => "many",
+            PluralCategory::Other => "other",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<PluralCategory> {
+        match name {
+            "zero" => Some(PluralCategory::Zero),
+            "one" => Some(PluralCategory::One),
+            "two" => Some(PluralCategory::Two),
+            "few" => Some(PluralCategory::Few),
+            "many" => Some(PluralCategory::Many),
+            "other" => Some(PluralCategory::Other),
+            _ => None,
+        }
+    }
+}
+
+/// Operands for plural rule evaluation per CLDR spec
+/// n = absolute value of the source number (i when t = 0, otherwise non-integral)
+/// i = integer digits of n
+/// v = number of visible fraction digits (with trailing zeros)
+/// w = number of visible fraction digits (without trailing zeros)
+/// f = visible fraction digits (with trailing zeros)
+/// t = visible fraction digits (without trailing zeros)
+/// e = compact decimal exponent (c is a synonym), e.g. 6 for "1.2M"
+#[derive(Clone, Copy, Debug)]
+pub struct PluralOperands {
+    pub i: u128,  // integer part
+    pub v: usize, // visible fraction digit count (with trailing zeros)
+    pub w: usize, // visible fraction digit count (without trailing zeros)
+    pub f: u128,  // fraction digits (with trailing zeros)
+    pub t: u128,  // fraction digits (without trailing zeros)
+    pub e: u32,   // compact decimal exponent
+}
+
+/// Which CLDR rule set to apply: counts ("1 item") or ranks ("1st place")
+#[cfg_attr(feature = "wasm-bindgen", wasm_bindgen::prelude::wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PluralRuleType {
+    Cardinal,
+    Ordinal,
+}
+
+/// Exact fixed-point decimal: `significand / 10^scale`, as written.
+/// "2.50" is (250, 2) and keeps its trailing zero; "1.2c6" is (1200000, 0)
+/// with exponent 6.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct FixedDecimal {
+    significand: u128,
+    scale: u32,
+    exponent: u32,
+}
+
+impl FixedDecimal {
+    /// Parse an unsigned-or-signed decimal literal, with optional CLDR
+    /// compact exponent ("1.2c6" / "1.2e6"). The sign is discarded.
+    fn parse(num: &str) -> Option<Self> {
+        let trimmed = num.trim();
+        let unsigned = trimmed
+            .strip_prefix('-')
+            .or_else(|| trimmed.strip_prefix('+'))
+            .unwrap_or(trimmed);
+
+        let (mantissa, exponent) = match unsigned.split_once(['c', 'e']) {
+            Some((mantissa, exponent)) => (mantissa, exponent.parse::<u32>().ok()?),
+            None => (unsigned, 0),
+        };
+
+        let (int_digits, frac_digits) = match mantissa.split_once('.') {
+            Some((int_part, frac_part)) if !frac_part.is_empty() => (int_part, frac_part),
+            Some(_) => return None,
+            None => (mantissa, ""),
+        };
+
+        let all_digits = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+        if int_digits.is_empty() || !all_digits(int_digits) || !all_digits(frac_digits) {
+            return None;
+        }
+
+        let mut significand: u128 = 0;
+        for digit in int_digits.bytes().chain(frac_digits.bytes()) {
+            significand = significand.checked_mul(10)?.checked_add((digit - b'0') as u128)?;
+        }
+
+        // Move the decimal point right by the exponent: 1.2c6 -> 1200000
+        let mut scale = frac_digits.len() as u32;
+        if exponent <= scale {
+            scale -= exponent;
+        } else {
+            significand = significand.checked_mul(10u128.checked_pow(exponent - scale)?)?;
+            scale = 0;
+        }
+
+        Some(FixedDecimal { significand, scale, exponent })
+    }
+
+    /// Exact value of an f64 via its shortest round-trip representation,
+    /// so 0.1 is "0.1" rather than 0.1000000000000000055...
+    fn from_f64(num: f64) -> Option<Self> {
+        if !num.is_finite() {
+            return None;
+        }
+        FixedDecimal::parse(&format!("{}", num.abs()))
+    }
+
+    fn operands(&self) -> PluralOperands {
+        let divisor = 10u128.pow(self.scale);
+        let i = self.significand / divisor;
+        let f = self.significand % divisor;
+
+        let mut t = f;
+        let mut w = self.scale as usize;
+        while w > 0 && t.is_multiple_of(10) {
+            t /= 10;
+            w -= 1;
+        }
+
+        PluralOperands { i, v: self.scale as usize, w, f, t, e: self.exponent }
+    }
+}
+
+impl PluralOperands {
+    pub fn from_f64(num: f64) -> Self {
+        FixedDecimal::from_f64(num)
+            .map(|decimal| decimal.operands())
+            // NaN, infinities and values beyond 38 digits: saturate to an integer
+            .unwrap_or(PluralOperands { i: num.abs() as u128, v: 0, w: 0, f: 0, t: 0, e: 0 })
+    }
+
+    pub fn from_integer(num: i128) -> Self {
+        PluralOperands { i: num.unsigned_abs(), v: 0, w: 0, f: 0, t: 0, e: 0 }
+    }
+
+    /// Parse the literal decimal representation, so "1.0" keeps v=1.
+    /// Accepts CLDR compact notation ("1.2c6") for the exponent operand.
+    pub fn from_decimal_str(num: &str) -> Option<Self> {
+        FixedDecimal::parse(num).map(|decimal| decimal.operands())
+    }
+}
+
+/// CLDR Plural Rules Engine
+/// Implements cardinal and ordinal plural rules for every CLDR locale
+#[derive(Clone, Debug)]
+pub struct PluralRules {
+    locale: String,
+    rule_type: PluralRuleType,
+}
+
+impl PluralRules {
+    pub fn new(locale: &str) -> PluralRules {
+        PluralRules::with_type(locale, PluralRuleType::Cardinal)
+    }
+
+    /// Create rules of a specific type (cardinal or ordinal)
+    pub fn with_type(locale: &str, rule_type: PluralRuleType) -> PluralRules {
+        // Keep the full tag; rules resolve region-first (pt-PT before pt)
+        PluralRules {
+            locale: canonicalize_locale(locale).to_lowercase(),
+            rule_type,
+        }
+    }
+
+    /// The requested locale, canonicalized and lowercased
+    pub fn locale(&self) -> &str {
+        &self.locale
+    }
+
+    pub fn rule_type(&self) -> PluralRuleType {
+        self.rule_type
+    }
+
+    /// Select the plural category for a number using this instance's rule type
+    pub fn select(&self, n: f64) -> PluralCategory {
+        self.select_operands(PluralOperands::from_f64(n))
+    }
+
+    /// Select the plural category for a decimal string such as "1.0" or "2.50",
+    /// preserving visible fraction digits that an f64 cannot represent;
+    /// None if it is not a decimal number
+    pub fn select_decimal(&self, n: &str) -> Option<PluralCategory> {
+        PluralOperands::from_decimal_str(n).map(|op| self.select_operands(op))
+    }
+
+    /// Select the plural category for an integer, exact beyond 2^53
+    pub fn select_integer(&self, n: i128) -> PluralCategory {
+        self.select_operands(PluralOperands::from_integer(n))
+    }
+
+    pub fn select_operands(&self, op: PluralOperands) -> PluralCategory {
+        self.select_with_type(self.rule_type, op)
+    }
+
+    /// Select with a rule type other than this instance's
+    pub fn select_with_type(&self, rule_type: PluralRuleType, op: PluralOperands) -> PluralCategory {
+        evaluate_rule_set(self.rule_set(rule_type), &op)
+    }
+
+    /// The CLDR locale whose rules apply (e.g. "pt-pt", or "pt" for "pt-br")
+    pub fn resolved_locale(&self, rule_type: PluralRuleType) -> Option<&'static str> {
+        self.resolve(rule_type).map(|(locale, _)| locale)
+    }
+
+    /// Whether CLDR has rules for this locale, rather than the default
+    pub fn has_rules(&self, rule_type: PluralRuleType) -> bool {
+        self.resolve_exact(rule_type).is_some()
+    }
+
+    pub fn rule_set(&self, rule_type: PluralRuleType) -> RuleSet {
+        self.resolve(rule_type)
+            .map(|(_, index)| cldr::RULE_SETS[index])
+            .unwrap_or(&[])
+    }
+
+    /// Raw CLDR sample text per category
+    pub fn sample_set(&self, rule_type: PluralRuleType) -> &'static [(PluralCategory, &'static str)] {
+        self.resolve(rule_type)
+            .map(|(_, index)| cldr::SAMPLE_SETS[index])
+            .unwrap_or(&[])
+    }
+
+    /// Expanded @integer and @decimal samples for one category
+    pub fn samples(&self, rule_type: PluralRuleType, category: PluralCategory) -> Vec<String> {
+        self.sample_set(rule_type)
+            .iter()
+            .find(|(candidate, _)| *candidate == category)
+            .map(|(_, text)| expand_samples(text))
+            .unwrap_or_default()
+    }
+
+    /// Categories this locale distinguishes, in CLDR order, always ending in "other"
+    pub fn categories(&self, rule_type: PluralRuleType) -> Vec<PluralCategory> {
+        let mut categories: Vec<PluralCategory> = self
+            .rule_set(rule_type)
+            .iter()
+            .map(|(category, _)| *category)
+            .collect();
+        categories.push(PluralCategory::Other);
+        categories
+    }
+
+    /// Find the CLDR locale whose rules apply: the full tag, then each
+    /// shorter prefix (pt-pt -> pt), then the default for the rule type
+    fn resolve(&self, rule_type: PluralRuleType) -> Option<(&'static str, usize)> {
+        self.resolve_exact(rule_type).or_else(|| match rule_type {
+            // Locales without CLDR data keep the historical one/other default
+            PluralRuleType::Cardinal => lookup_rule_index(cldr::CARDINAL_LOCALES, "en"),
+            PluralRuleType::Ordinal => lookup_rule_index(cldr::ORDINAL_LOCALES, "root"),
+        })
+    }
+
+    /// Like `resolve`, without the default: None when CLDR has no data
+    fn resolve_exact(&self, rule_type: PluralRuleType) -> Option<(&'static str, usize)> {
+        let mut candidate = self.locale.as_str();
+        loop {
+            if let Some(found) = lookup_rule_index(locale_table(rule_type), candidate) {
+                return Some(found);
+            }
+            match candidate.rfind('-') {
+                Some(pos) => candidate = &candidate[..pos],
+                None => return None,
+            }
+        }
+    }
+}
+
+/// CLDR release the compiled plural rules were generated from
+pub const CLDR_VERSION: &str = cldr::CLDR_VERSION;
+
+/// Locales (lowercase, e.g. "pt-pt") compiled in for a rule type, in order,
+/// without the "root" default
+pub fn supported_locales(rule_type: PluralRuleType) -> impl Iterator<Item = &'static str> {
+    locale_table(rule_type)
+        .iter()
+        .map(|(locale, _)| *locale)
+        .filter(|locale| *locale != "root")
+}
+
+// ============================================================================
+// CLDR rule tables - generated by build.rs from data/cldr/*.json
+// ============================================================================
+
+/// Operand referenced by a rule relation
+#[derive(Clone, Copy, Debug)]
+pub enum Operand {
+    N,
+    I,
+    V,
+    W,
+    F,
+    T,
+    E,
+}
+
+/// One relation of a CLDR rule, e.g. `i % 100 != 11..19`
+/// A modulus of 0 means the operand is compared as-is.
+#[derive(Debug)]
+pub struct Relation {
+    pub operand: Operand,
+    pub modulus: u64,
+    pub negated: bool,
+    pub ranges: &'static [(u64, u64)],
+}
+
+/// Category conditions in CLDR order; each condition is an OR of AND-chains.
+/// A number matching no condition is "other".
+pub type RuleSet = &'static [(PluralCategory, &'static [&'static [Relation]])];
+
+mod cldr {
+    use super::{Operand, PluralCategory, Relation, RuleSet};
+
+    include!(concat!(env!("OUT_DIR"), "/plural_rules.rs"));
+}
+
+fn locale_table(rule_type: PluralRuleType) -> &'static [(&'static str, u16)] {
+    match rule_type {
+        PluralRuleType::Cardinal => cldr::CARDINAL_LOCALES,
+        PluralRuleType::Ordinal => cldr::ORDINAL_LOCALES,
+    }
+}
+
+fn lookup_rule_index(table: &'static [(&'static str, u16)], locale: &str) -> Option<(&'static str, usize)> {
+    table
+        .binary_search_by(|(candidate, _)| (*candidate).cmp(locale))
+        .ok()
+        .map(|index| (table[index].0, table[index].1 as usize))
+}
+
+/// Expand CLDR sample text ("@integer 2~4, 22, … @decimal 0.0~0.2") into
+/// individual values; ranges step by the last visible digit, "…" is dropped
+fn expand_samples(text: &str) -> Vec<String> {
+    let mut samples = Vec::new();
+    let items = text
+        .split(['@', ','])
+        .map(|item| item.trim().trim_start_matches("integer").trim_start_matches("decimal").trim())
+        .filter(|item| !item.is_empty() && *item != "…");
+
+    for item in items {
+        let Some((low, high)) = item.split_once('~') else {
+            samples.push(item.to_string());
+            continue;
+        };
+
+        let scale = low.split_once('.').map(|(_, frac)| frac.len()).unwrap_or(0);
+        let to_scaled = |s: &str| s.replace('.', "").parse::<u64>().ok();
+        let (Some(start), Some(end)) = (to_scaled(low), to_scaled(high)) else {
+            samples.push(item.to_string());
+            continue;
+        };
+
+        for value in start..=end {
+            if scale == 0 {
+                samples.push(value.to_string());
+            } else {
+                let divisor = 10u64.pow(scale as u32);
+                samples.push(format!("{}.{:0width$}", value / divisor, value % divisor, width = scale));
+            }
+        }
+    }
+
+    samples
+}
+
+/// The first category whose condition the operands meet, else "other"
+pub fn evaluate_rule_set(rule_set: RuleSet, op: &PluralOperands) -> PluralCategory {
+    rule_set
+        .iter()
+        .find(|(_, condition)| {
+            condition
+                .iter()
+                .any(|and_chain| and_chain.iter().all(|relation| relation.matches(op)))
+        })
+        .map(|(category, _)| *category)
+        .unwrap_or(PluralCategory::Other)
+}
+
+impl Relation {
+    pub fn matches(&self, op: &PluralOperands) -> bool {
+        let value = match self.operand {
+            // n only equals an integer when it has no visible non-zero fraction
+            Operand::N => if op.t == 0 { Some(op.i) } else { None },
+            Operand::I => Some(op.i),
+            Operand::V => Some(op.v as u128),
+            Operand::W => Some(op.w as u128),
+            Operand::F => Some(op.f),
+            Operand::T => Some(op.t),
+            Operand::E => Some(op.e as u128),
+        };
+
+        let in_ranges = value.is_some_and(|value| {
+            let value = if self.modulus == 0 { value } else { value % self.modulus as u128 };
+            self.ranges
+                .iter()
+                .any(|&(low, high)| (low as u128..=high as u128).contains(&value))
+        });
+
+        in_ranges != self.negated
+    }
+}
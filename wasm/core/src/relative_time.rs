@@ -0,0 +1,44 @@
+// Relative time strings behind i18n-wasm's RelativeTimeFormat
+//
+// Compiled by build.rs from data/relative_time.json with the same locale
+// selection as the plural tables, so a trimmed build drops both together.
+// English is always compiled in; languages without an entry, and strings an
+// entry leaves out, fall back to it.
+
+/// One language's relative time strings
+#[derive(Debug)]
+pub struct RelativeTimeData {
+    pub language: &'static str,
+    /// "just now"
+    pub now: Option<&'static str>,
+    /// Phrasing with {0} for the count and {1} for the unit: "{0} {1} ago"
+    pub past: Option<&'static str>,
+    pub future: Option<&'static str>,
+    /// Words for one unit back and ahead: ("day", "yesterday", "tomorrow")
+    pub relative: &'static [(&'static str, &'static str, &'static str)],
+    /// Unit names by style, singular and plural: ("long", "day", "day", "days")
+    pub units: &'static [(&'static str, &'static str, &'static str, &'static str)],
+}
+
+mod data {
+    use super::RelativeTimeData;
+
+    include!(concat!(env!("OUT_DIR"), "/relative_time.rs"));
+}
+
+/// Where the relative time strings come from
+pub const RELATIVE_TIME_SOURCE: &str = data::SOURCE;
+
+/// Languages compiled in with every relative time string
+pub const RELATIVE_TIME_FULL_LOCALES: &[&str] = data::FULL_LOCALES;
+
+/// Languages compiled in with only some, the rest coming from English
+pub const RELATIVE_TIME_PARTIAL_LOCALES: &[&str] = data::PARTIAL_LOCALES;
+
+/// A language's relative time strings, if compiled in
+pub fn relative_time_data(language: &str) -> Option<&'static RelativeTimeData> {
+    data::LOCALES
+        .binary_search_by(|data| data.language.cmp(language))
+        .ok()
+        .map(|index| &data::LOCALES[index])
+}
@@ -0,0 +1,41 @@
+/*
+ * i18n-wasm C ABI - build the crate natively with `cargo build --release
+ * --features ffi` and link or dlopen libi18n_wasm.
+ *
+ * Strings are NUL-terminated UTF-8. i18n_translate and
+ * i18n_translate_plural write into the caller's buffer like snprintf: they
+ * return the full length in bytes, and a result >= capacity means the text
+ * was truncated. Negative results are the I18N_* error codes.
+ */
+#ifndef I18N_H
+#define I18N_H
+
+#include <stddef.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define I18N_OK 0
+#define I18N_INVALID_ARGUMENT -1 /* null pointer or invalid UTF-8 */
+#define I18N_INVALID_JSON -2     /* malformed config or catalog */
+#define I18N_MISSING_KEY -3      /* strict mode and no locale has the key */
+
+typedef struct I18nWasm I18nWasm;
+
+/* NULL if the config is invalid */
+I18nWasm *i18n_create(const char *config_json);
+void i18n_destroy(I18nWasm *instance);
+
+int i18n_load_catalog(I18nWasm *instance, const char *locale, const char *catalog_json);
+int i18n_set_locale(I18nWasm *instance, const char *locale);
+
+ptrdiff_t i18n_translate(const I18nWasm *instance, const char *key, char *buffer, size_t capacity);
+ptrdiff_t i18n_translate_plural(const I18nWasm *instance, const char *key, double count, char *buffer,
+                                size_t capacity);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* I18N_H */
@@ -0,0 +1,11 @@
+// Build script: leave the napi_* symbols for Node to resolve at load time
+//
+// ELF linkers allow undefined symbols in shared objects already; Apple's
+// needs to be told.
+use std::env;
+
+fn main() {
+    if env::var("CARGO_CFG_TARGET_OS").as_deref() == Ok("macos") {
+        println!("cargo:rustc-cdylib-link-arg=-Wl,-undefined,dynamic_lookup");
+    }
+}
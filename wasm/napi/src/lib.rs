@@ -0,0 +1,471 @@
+// i18n-napi - native Node.js addon over i18n-core
+//
+// Exports the PluralRules class, the PluralCategory / PluralRuleType enums
+// and the standalone plural functions with the names, arguments and return
+// values of the wasm build, so server code can require the .node file in its
+// place without the copy into wasm memory on every call. Written against
+// Node-API version 6 directly: Node supplies the napi_* functions when it
+// loads the addon.
+//
+// Scope: this is the plural surface only. I18nWasm (catalog loading,
+// translate*) is not exported. Its engine lives in the i18n-wasm crate on
+// wasm-bindgen and js-sys types, and has to move into i18n-core before an
+// addon can share it. The bindings are hand-written because napi-rs is not
+// a dependency this workspace can build with. sys.rs declares only the
+// Node-API calls made here.
+mod sys;
+
+use std::ffi::{c_void, CStr, CString};
+use std::ptr;
+
+use i18n_core::{
+    canonicalize_locale, supported_locales, PluralCategory, PluralOperands, PluralRuleType, PluralRules,
+    CLDR_VERSION,
+};
+use serde::Serialize;
+use sys::{Callback, CallbackInfo, Env, PropertyDescriptor, Value};
+
+/// Variant names in discriminant order, as wasm-bindgen exports the enums
+const CATEGORY_NAMES: [&str; 6] = ["Zero", "One", "Two", "Few", "Many", "Other"];
+const RULE_TYPE_NAMES: [&str; 2] = ["Cardinal", "Ordinal"];
+
+type Result<T> = std::result::Result<T, String>;
+
+/// Called by Node when the addon is loaded
+///
+/// # Safety
+///
+/// `env` and `exports` must be the handles Node passes to a module's
+/// registration function.
+#[no_mangle]
+pub unsafe extern "C" fn napi_register_module_v1(env: Env, exports: Value) -> Value {
+    let functions: [(&CStr, Callback); 9] = [
+        (c"canonicalizeLocale", Some(canonicalize_locale_fn)),
+        (c"getPluralCategory", Some(get_plural_category)),
+        (c"getPluralCategoryBigInt", Some(get_plural_category_big_int)),
+        (c"getOrdinalCategory", Some(get_ordinal_category)),
+        (c"getPluralCategories", Some(get_plural_categories)),
+        (c"getPluralSamples", Some(get_plural_samples)),
+        (c"getSupportedPluralLocales", Some(get_supported_plural_locales)),
+        (c"getCldrVersion", Some(get_cldr_version)),
+        (c"selectMany", Some(select_many_fn)),
+    ];
+
+    let register = || -> Result<Value> {
+        for (name, callback) in functions {
+            let mut function = ptr::null_mut();
+            check(sys::napi_create_function(env, name.as_ptr(), sys::AUTO_LENGTH, callback, ptr::null_mut(), &mut function))?;
+            set(env, exports, name, function)?;
+        }
+        set(env, exports, c"PluralRules", plural_rules_class(env)?)?;
+        set(env, exports, c"PluralCategory", enum_object(env, &CATEGORY_NAMES)?)?;
+        set(env, exports, c"PluralRuleType", enum_object(env, &RULE_TYPE_NAMES)?)?;
+        Ok(exports)
+    };
+    finish(env, register())
+}
+
+// ============================================================================
+// PluralRules class
+// ============================================================================
+
+unsafe fn plural_rules_class(env: Env) -> Result<Value> {
+    let methods: [(&CStr, Callback, i32); 13] = [
+        (c"withType", Some(with_type), sys::STATIC | sys::DEFAULT_METHOD),
+        (c"select", Some(select), sys::DEFAULT_METHOD),
+        (c"selectMany", Some(select_many), sys::DEFAULT_METHOD),
+        (c"selectStr", Some(select_str), sys::DEFAULT_METHOD),
+        (c"selectBigInt", Some(select_big_int), sys::DEFAULT_METHOD),
+        (c"selectCompact", Some(select_compact), sys::DEFAULT_METHOD),
+        (c"selectOrdinal", Some(select_ordinal), sys::DEFAULT_METHOD),
+        (c"selectString", Some(select_string), sys::DEFAULT_METHOD),
+        (c"getLocale", Some(get_locale), sys::DEFAULT_METHOD),
+        (c"getResolvedPluralLocale", Some(get_resolved_plural_locale), sys::DEFAULT_METHOD),
+        (c"getRuleType", Some(get_rule_type), sys::DEFAULT_METHOD),
+        (c"getCategories", Some(get_categories), sys::DEFAULT_METHOD),
+        (c"getSamples", Some(get_samples), sys::DEFAULT_METHOD),
+    ];
+    let properties: Vec<PropertyDescriptor> = methods
+        .iter()
+        .map(|&(name, method, attributes)| PropertyDescriptor {
+            utf8name: name.as_ptr(),
+            name: ptr::null_mut(),
+            method,
+            getter: None,
+            setter: None,
+            value: ptr::null_mut(),
+            attributes,
+            data: ptr::null_mut(),
+        })
+        .collect();
+
+    let mut class = ptr::null_mut();
+    check(sys::napi_define_class(
+        env,
+        c"PluralRules".as_ptr(),
+        sys::AUTO_LENGTH,
+        Some(construct),
+        ptr::null_mut(),
+        properties.len(),
+        properties.as_ptr(),
+        &mut class,
+    ))?;
+    Ok(class)
+}
+
+/// new PluralRules(locale), or with a PluralRuleType as the second argument
+unsafe extern "C" fn construct(env: Env, info: CallbackInfo) -> Value {
+    run(env, info, |call| {
+        let locale = call.string(0)?;
+        let rule_type = match call.argc {
+            0 | 1 => PluralRuleType::Cardinal,
+            _ => rule_type(call.u32(1)?)?,
+        };
+        call.wrap(PluralRules::with_type(&locale, rule_type))
+    })
+}
+
+/// PluralRules.withType(locale, ruleType); `this` is the class
+unsafe extern "C" fn with_type(env: Env, info: CallbackInfo) -> Value {
+    run(env, info, |call| {
+        let mut instance = ptr::null_mut();
+        check(unsafe { sys::napi_new_instance(env, call.this, 2, call.args.as_ptr(), &mut instance) })?;
+        Ok(instance)
+    })
+}
+
+unsafe extern "C" fn select(env: Env, info: CallbackInfo) -> Value {
+    run(env, info, |call| category_value(env, call.rules()?.select(call.f64(0)?)))
+}
+
+unsafe extern "C" fn select_many(env: Env, info: CallbackInfo) -> Value {
+    run(env, info, |call| {
+        let rules = call.rules()?;
+        let indices: Vec<u8> = call.f64s(0)?.into_iter().map(|n| rules.select(n) as u8).collect();
+        buffer_value(env, &indices)
+    })
+}
+
+unsafe extern "C" fn select_str(env: Env, info: CallbackInfo) -> Value {
+    run(env, info, |call| {
+        let n = call.string(0)?;
+        let category = call.rules()?.select_decimal(&n).ok_or_else(|| format!("Invalid number: {}", n))?;
+        category_value(env, category)
+    })
+}
+
+unsafe extern "C" fn select_big_int(env: Env, info: CallbackInfo) -> Value {
+    run(env, info, |call| category_value(env, call.rules()?.select_integer(call.i128(0)?)))
+}
+
+/// selectCompact(mantissa, exponent): "1.2M" is (1.2, 6)
+unsafe extern "C" fn select_compact(env: Env, info: CallbackInfo) -> Value {
+    run(env, info, |call| {
+        let n = format!("{}c{}", call.f64(0)?, call.u32(1)?);
+        let category = call.rules()?.select_decimal(&n).ok_or_else(|| format!("Invalid number: {}", n))?;
+        category_value(env, category)
+    })
+}
+
+unsafe extern "C" fn select_ordinal(env: Env, info: CallbackInfo) -> Value {
+    run(env, info, |call| {
+        let op = PluralOperands::from_f64(call.f64(0)?);
+        category_value(env, call.rules()?.select_with_type(PluralRuleType::Ordinal, op))
+    })
+}
+
+unsafe extern "C" fn select_string(env: Env, info: CallbackInfo) -> Value {
+    run(env, info, |call| string_value(env, call.rules()?.select(call.f64(0)?).as_str()))
+}
+
+unsafe extern "C" fn get_locale(env: Env, info: CallbackInfo) -> Value {
+    run(env, info, |call| string_value(env, call.rules()?.locale()))
+}
+
+unsafe extern "C" fn get_resolved_plural_locale(env: Env, info: CallbackInfo) -> Value {
+    run(env, info, |call| {
+        let rules = call.rules()?;
+        let resolved = rules.resolved_locale(rules.rule_type()).map(canonicalize_locale).unwrap_or_default();
+        string_value(env, &resolved)
+    })
+}
+
+unsafe extern "C" fn get_rule_type(env: Env, info: CallbackInfo) -> Value {
+    run(env, info, |call| u32_value(env, call.rules()?.rule_type() as u32))
+}
+
+unsafe extern "C" fn get_categories(env: Env, info: CallbackInfo) -> Value {
+    run(env, info, |call| {
+        let rules = call.rules()?;
+        let names: Vec<&str> = rules.categories(rules.rule_type()).iter().map(|c| c.as_str()).collect();
+        json_value(env, &names)
+    })
+}
+
+unsafe extern "C" fn get_samples(env: Env, info: CallbackInfo) -> Value {
+    run(env, info, |call| {
+        let rules = call.rules()?;
+        json_value(env, &samples(rules, &call.string(0)?))
+    })
+}
+
+// ============================================================================
+// Standalone functions
+// ============================================================================
+
+unsafe extern "C" fn canonicalize_locale_fn(env: Env, info: CallbackInfo) -> Value {
+    run(env, info, |call| string_value(env, &canonicalize_locale(&call.string(0)?)))
+}
+
+unsafe extern "C" fn get_plural_category(env: Env, info: CallbackInfo) -> Value {
+    run(env, info, |call| {
+        let category = PluralRules::new(&call.string(0)?).select(call.f64(1)?);
+        string_value(env, category.as_str())
+    })
+}
+
+unsafe extern "C" fn get_plural_category_big_int(env: Env, info: CallbackInfo) -> Value {
+    run(env, info, |call| {
+        let category = PluralRules::new(&call.string(0)?).select_integer(call.i128(1)?);
+        string_value(env, category.as_str())
+    })
+}
+
+unsafe extern "C" fn get_ordinal_category(env: Env, info: CallbackInfo) -> Value {
+    run(env, info, |call| {
+        let category = PluralRules::with_type(&call.string(0)?, PluralRuleType::Ordinal).select(call.f64(1)?);
+        string_value(env, category.as_str())
+    })
+}
+
+/// Plural categories used by a locale, per rule type
+#[derive(Serialize)]
+struct PluralCategorySet {
+    cardinal: Vec<&'static str>,
+    ordinal: Vec<&'static str>,
+}
+
+unsafe extern "C" fn get_plural_categories(env: Env, info: CallbackInfo) -> Value {
+    run(env, info, |call| {
+        let rules = PluralRules::new(&call.string(0)?);
+        let names = |rule_type| rules.categories(rule_type).iter().map(|c| c.as_str()).collect();
+        let categories = PluralCategorySet {
+            cardinal: names(PluralRuleType::Cardinal),
+            ordinal: names(PluralRuleType::Ordinal),
+        };
+        json_value(env, &categories)
+    })
+}
+
+unsafe extern "C" fn get_plural_samples(env: Env, info: CallbackInfo) -> Value {
+    run(env, info, |call| json_value(env, &samples(&PluralRules::new(&call.string(0)?), &call.string(1)?)))
+}
+
+unsafe extern "C" fn get_supported_plural_locales(env: Env, info: CallbackInfo) -> Value {
+    run(env, info, |_| json_value(env, &supported_locales(PluralRuleType::Cardinal).collect::<Vec<_>>()))
+}
+
+unsafe extern "C" fn get_cldr_version(env: Env, info: CallbackInfo) -> Value {
+    run(env, info, |_| string_value(env, CLDR_VERSION))
+}
+
+unsafe extern "C" fn select_many_fn(env: Env, info: CallbackInfo) -> Value {
+    run(env, info, |call| {
+        let rules = PluralRules::new(&call.string(0)?);
+        let indices: Vec<u8> = call.f64s(1)?.into_iter().map(|n| rules.select(n) as u8).collect();
+        buffer_value(env, &indices)
+    })
+}
+
+/// Expanded CLDR samples for a category name, empty for unknown names
+fn samples(rules: &PluralRules, category: &str) -> Vec<String> {
+    PluralCategory::from_name(category)
+        .map(|category| rules.samples(rules.rule_type(), category))
+        .unwrap_or_default()
+}
+
+fn rule_type(value: u32) -> Result<PluralRuleType> {
+    match value {
+        0 => Ok(PluralRuleType::Cardinal),
+        1 => Ok(PluralRuleType::Ordinal),
+        _ => Err(format!("Invalid rule type: {}", value)),
+    }
+}
+
+// ============================================================================
+// Node-API plumbing
+// ============================================================================
+
+/// Arguments (up to two) and receiver of one call from JS
+struct Call {
+    env: Env,
+    this: Value,
+    args: [Value; 2],
+    /// Number of arguments actually passed
+    argc: usize,
+}
+
+/// Run a callback body, throwing its error as a JS Error
+unsafe fn run(env: Env, info: CallbackInfo, body: impl FnOnce(&Call) -> Result<Value>) -> Value {
+    let mut call = Call { env, this: ptr::null_mut(), args: [ptr::null_mut(); 2], argc: 2 };
+    let status = sys::napi_get_cb_info(env, info, &mut call.argc, call.args.as_mut_ptr(), &mut call.this, ptr::null_mut());
+    finish(env, check(status).and_then(|()| body(&call)))
+}
+
+unsafe fn finish(env: Env, result: Result<Value>) -> Value {
+    match result {
+        Ok(value) => value,
+        Err(message) => {
+            let message = CString::new(message).unwrap_or_default();
+            sys::napi_throw_error(env, ptr::null(), message.as_ptr());
+            ptr::null_mut()
+        }
+    }
+}
+
+fn check(status: sys::Status) -> Result<()> {
+    match status {
+        sys::OK => Ok(()),
+        status => Err(format!("Node-API call failed with status {}", status)),
+    }
+}
+
+impl Call {
+    fn arg(&self, index: usize) -> Result<Value> {
+        match index < self.argc.min(self.args.len()) {
+            true => Ok(self.args[index]),
+            false => Err(format!("Missing argument {}", index + 1)),
+        }
+    }
+
+    fn expected(index: usize, what: &str) -> String {
+        format!("Argument {} must be {}", index + 1, what)
+    }
+
+    fn string(&self, index: usize) -> Result<String> {
+        let value = self.arg(index)?;
+        let mut length = 0;
+        check(unsafe { sys::napi_get_value_string_utf8(self.env, value, ptr::null_mut(), 0, &mut length) })
+            .map_err(|_| Call::expected(index, "a string"))?;
+        let mut bytes = vec![0u8; length + 1];
+        check(unsafe {
+            sys::napi_get_value_string_utf8(self.env, value, bytes.as_mut_ptr().cast(), bytes.len(), &mut length)
+        })?;
+        bytes.truncate(length);
+        String::from_utf8(bytes).map_err(|e| e.to_string())
+    }
+
+    fn f64(&self, index: usize) -> Result<f64> {
+        let mut number = 0.0;
+        check(unsafe { sys::napi_get_value_double(self.env, self.arg(index)?, &mut number) })
+            .map_err(|_| Call::expected(index, "a number"))?;
+        Ok(number)
+    }
+
+    fn u32(&self, index: usize) -> Result<u32> {
+        let mut number = 0;
+        check(unsafe { sys::napi_get_value_uint32(self.env, self.arg(index)?, &mut number) })
+            .map_err(|_| Call::expected(index, "a number"))?;
+        Ok(number)
+    }
+
+    /// A BigInt that fits in an i128
+    fn i128(&self, index: usize) -> Result<i128> {
+        let (mut sign, mut count, mut words) = (0, 2, [0u64; 2]);
+        check(unsafe {
+            sys::napi_get_value_bigint_words(self.env, self.arg(index)?, &mut sign, &mut count, words.as_mut_ptr())
+        })
+        .map_err(|_| Call::expected(index, "a BigInt"))?;
+
+        let magnitude = (words[1] as u128) << 64 | words[0] as u128;
+        let magnitude = i128::try_from(magnitude).ok().filter(|_| count <= 2);
+        let magnitude = magnitude.ok_or_else(|| Call::expected(index, "a BigInt within 128 bits"))?;
+        Ok(if sign == 0 { magnitude } else { -magnitude })
+    }
+
+    /// An array or typed array of numbers
+    fn f64s(&self, index: usize) -> Result<Vec<f64>> {
+        let array = self.arg(index)?;
+        let mut length = 0;
+        check(unsafe { sys::napi_get_array_length(self.env, array, &mut length) })
+            .map_err(|_| Call::expected(index, "an array of numbers"))?;
+        (0..length)
+            .map(|item| {
+                let (mut value, mut number) = (ptr::null_mut(), 0.0);
+                check(unsafe { sys::napi_get_element(self.env, array, item, &mut value) })?;
+                check(unsafe { sys::napi_get_value_double(self.env, value, &mut number) })
+                    .map_err(|_| Call::expected(index, "an array of numbers"))?;
+                Ok(number)
+            })
+            .collect()
+    }
+
+    /// Attach the rules to a new instance; freed when it is collected
+    fn wrap(&self, rules: PluralRules) -> Result<Value> {
+        unsafe extern "C" fn free(_env: Env, data: *mut c_void, _hint: *mut c_void) {
+            drop(Box::from_raw(data.cast::<PluralRules>()));
+        }
+
+        let data = Box::into_raw(Box::new(rules)).cast::<c_void>();
+        let status = unsafe { sys::napi_wrap(self.env, self.this, data, Some(free), ptr::null_mut(), ptr::null_mut()) };
+        if let Err(e) = check(status) {
+            drop(unsafe { Box::from_raw(data.cast::<PluralRules>()) });
+            return Err(e);
+        }
+        Ok(self.this)
+    }
+
+    fn rules(&self) -> Result<&PluralRules> {
+        let mut data = ptr::null_mut();
+        check(unsafe { sys::napi_unwrap(self.env, self.this, &mut data) })
+            .map_err(|_| "Not a PluralRules instance".to_string())?;
+        // Lives until the instance is collected, which `this` prevents
+        Ok(unsafe { &*data.cast::<PluralRules>() })
+    }
+}
+
+fn string_value(env: Env, text: &str) -> Result<Value> {
+    let mut value = ptr::null_mut();
+    check(unsafe { sys::napi_create_string_utf8(env, text.as_ptr().cast(), text.len(), &mut value) })?;
+    Ok(value)
+}
+
+fn json_value(env: Env, value: &impl Serialize) -> Result<Value> {
+    string_value(env, &serde_json::to_string(value).map_err(|e| e.to_string())?)
+}
+
+fn u32_value(env: Env, number: u32) -> Result<Value> {
+    let mut value = ptr::null_mut();
+    check(unsafe { sys::napi_create_uint32(env, number, &mut value) })?;
+    Ok(value)
+}
+
+fn category_value(env: Env, category: PluralCategory) -> Result<Value> {
+    u32_value(env, category as u32)
+}
+
+/// A Buffer, which is a Uint8Array as the wasm build returns
+fn buffer_value(env: Env, bytes: &[u8]) -> Result<Value> {
+    let mut value = ptr::null_mut();
+    check(unsafe {
+        sys::napi_create_buffer_copy(env, bytes.len(), bytes.as_ptr().cast(), ptr::null_mut(), &mut value)
+    })?;
+    Ok(value)
+}
+
+/// { Zero: 0, "0": "Zero", ... }, the shape of a wasm-bindgen enum
+fn enum_object(env: Env, names: &[&str]) -> Result<Value> {
+    let mut object = ptr::null_mut();
+    check(unsafe { sys::napi_create_object(env, &mut object) })?;
+    for (index, name) in names.iter().enumerate() {
+        let name_key = CString::new(*name).map_err(|e| e.to_string())?;
+        let index_key = CString::new(index.to_string()).map_err(|e| e.to_string())?;
+        set(env, object, &name_key, u32_value(env, index as u32)?)?;
+        set(env, object, &index_key, string_value(env, name)?)?;
+    }
+    Ok(object)
+}
+
+fn set(env: Env, object: Value, name: &CStr, value: Value) -> Result<()> {
+    check(unsafe { sys::napi_set_named_property(env, object, name.as_ptr(), value) })
+}
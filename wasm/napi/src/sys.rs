@@ -0,0 +1,104 @@
+// Node-API declarations - the subset of js_native_api.h / node_api.h used here
+//
+// Every function returns a napi_status, 0 (napi_ok) on success.
+use std::ffi::{c_char, c_int, c_void};
+
+pub type Env = *mut c_void;
+pub type Value = *mut c_void;
+pub type CallbackInfo = *mut c_void;
+pub type Ref = *mut c_void;
+pub type Status = c_int;
+pub type Callback = Option<unsafe extern "C" fn(Env, CallbackInfo) -> Value>;
+pub type Finalize = Option<unsafe extern "C" fn(Env, *mut c_void, *mut c_void)>;
+
+pub const OK: Status = 0;
+/// NAPI_AUTO_LENGTH: the name is NUL-terminated
+pub const AUTO_LENGTH: usize = usize::MAX;
+/// napi_default_method: writable and configurable
+pub const DEFAULT_METHOD: c_int = 1 | 4;
+pub const STATIC: c_int = 1 << 10;
+
+#[repr(C)]
+pub struct PropertyDescriptor {
+    pub utf8name: *const c_char,
+    pub name: Value,
+    pub method: Callback,
+    pub getter: Callback,
+    pub setter: Callback,
+    pub value: Value,
+    pub attributes: c_int,
+    pub data: *mut c_void,
+}
+
+extern "C" {
+    pub fn napi_get_cb_info(
+        env: Env,
+        info: CallbackInfo,
+        argc: *mut usize,
+        argv: *mut Value,
+        this: *mut Value,
+        data: *mut *mut c_void,
+    ) -> Status;
+    pub fn napi_throw_error(env: Env, code: *const c_char, msg: *const c_char) -> Status;
+
+    pub fn napi_create_object(env: Env, result: *mut Value) -> Status;
+    pub fn napi_create_uint32(env: Env, value: u32, result: *mut Value) -> Status;
+    pub fn napi_create_string_utf8(env: Env, text: *const c_char, length: usize, result: *mut Value) -> Status;
+    pub fn napi_create_buffer_copy(
+        env: Env,
+        length: usize,
+        data: *const c_void,
+        result_data: *mut *mut c_void,
+        result: *mut Value,
+    ) -> Status;
+    pub fn napi_create_function(
+        env: Env,
+        name: *const c_char,
+        length: usize,
+        callback: Callback,
+        data: *mut c_void,
+        result: *mut Value,
+    ) -> Status;
+
+    pub fn napi_get_value_double(env: Env, value: Value, result: *mut f64) -> Status;
+    pub fn napi_get_value_uint32(env: Env, value: Value, result: *mut u32) -> Status;
+    pub fn napi_get_value_string_utf8(
+        env: Env,
+        value: Value,
+        buf: *mut c_char,
+        size: usize,
+        result: *mut usize,
+    ) -> Status;
+    pub fn napi_get_value_bigint_words(
+        env: Env,
+        value: Value,
+        sign_bit: *mut c_int,
+        word_count: *mut usize,
+        words: *mut u64,
+    ) -> Status;
+    pub fn napi_get_array_length(env: Env, value: Value, result: *mut u32) -> Status;
+    pub fn napi_get_element(env: Env, object: Value, index: u32, result: *mut Value) -> Status;
+    pub fn napi_set_named_property(env: Env, object: Value, name: *const c_char, value: Value) -> Status;
+
+    pub fn napi_define_class(
+        env: Env,
+        name: *const c_char,
+        length: usize,
+        constructor: Callback,
+        data: *mut c_void,
+        property_count: usize,
+        properties: *const PropertyDescriptor,
+        result: *mut Value,
+    ) -> Status;
+    pub fn napi_new_instance(env: Env, constructor: Value, argc: usize, argv: *const Value, result: *mut Value)
+        -> Status;
+    pub fn napi_wrap(
+        env: Env,
+        object: Value,
+        native: *mut c_void,
+        finalize: Finalize,
+        hint: *mut c_void,
+        result: *mut Ref,
+    ) -> Status;
+    pub fn napi_unwrap(env: Env, object: Value, result: *mut *mut c_void) -> Status;
+}
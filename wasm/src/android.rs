@@ -0,0 +1,95 @@
+// Android string resources - res/values*/strings.xml
+//
+// <string name="..."> entries load as strings, <string-array> as arrays and
+// <plurals name="..."> as plural blocks keyed by each <item quantity="...">. Values are unescaped the
+// way aapt does: \' \" \n \t \uXXXX escapes, double quotes preserve
+// whitespace, and unquoted whitespace runs collapse to one space. Markup such
+// as <xliff:g> or <b> keeps its text only. Positional placeholders (%1$s)
+// stay as written, except that %1$d / %1$s in plural items - the count, by
+// convention - become the %d / %s count placeholders.
+use super::xml::{self, Element};
+use super::{Catalog, PluralForms};
+
+pub(crate) fn parse(text: &str) -> Result<Catalog, String> {
+    let root = xml::parse(text)?;
+    if root.name != "resources" {
+        return Err(format!("expected <resources>, found <{}>", root.name));
+    }
+
+    let mut catalog = Catalog::default();
+    for element in root.elements() {
+        let name = || {
+            element
+                .attribute("name")
+                .map(String::from)
+                .ok_or_else(|| format!("<{}> without a name", element.name))
+        };
+        match element.name.as_str() {
+            "string" => {
+                catalog.strings.insert(name()?, unescape(&element.text())?.into());
+            }
+            "string-array" => {
+                let items = element
+                    .elements()
+                    .filter(|item| item.name == "item")
+                    .map(|item| unescape(&item.text()))
+                    .collect::<Result<Vec<_>, _>>()?;
+                catalog.arrays.insert(name()?, items);
+            }
+            "plurals" => {
+                let forms = plural_items(element)?;
+                let forms = PluralForms::from_object(&forms)
+                    .ok_or_else(|| format!("plurals {} has no other item", name().unwrap_or_default()))?;
+                catalog.plurals.insert(name()?, forms);
+            }
+            _ => {}
+        }
+    }
+    Ok(catalog)
+}
+
+fn plural_items(plurals: &Element) -> Result<serde_json::Map<String, serde_json::Value>, String> {
+    let mut forms = serde_json::Map::new();
+    for item in plurals.elements().filter(|element| element.name == "item") {
+        let quantity = item.attribute("quantity").ok_or("plural <item> without a quantity")?;
+        let value = unescape(&item.text())?.replace("%1$d", "%d").replace("%1$s", "%s");
+        forms.insert(quantity.to_string(), value.into());
+    }
+    Ok(forms)
+}
+
+/// Resolve aapt escapes, quoting and whitespace collapsing
+fn unescape(text: &str) -> Result<String, String> {
+    let mut result = String::with_capacity(text.len());
+    let mut quoted = false;
+    let mut pending_space = false;
+    let mut chars = text.chars();
+
+    while let Some(c) = chars.next() {
+        if c.is_whitespace() && !quoted {
+            pending_space = !result.is_empty();
+            continue;
+        }
+        if pending_space {
+            result.push(' ');
+            pending_space = false;
+        }
+
+        match c {
+            '"' => quoted = !quoted,
+            '\\' => match chars.next() {
+                Some('n') => result.push('\n'),
+                Some('t') => result.push('\t'),
+                Some('u') => {
+                    let hex: String = chars.by_ref().take(4).collect();
+                    let decoded = u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32);
+                    result.push(decoded.ok_or_else(|| format!("invalid \\u{} escape", hex))?);
+                }
+                Some(other) => result.push(other),
+                None => return Err("trailing backslash".to_string()),
+            },
+            _ => result.push(c),
+        }
+    }
+    Ok(result)
+}
@@ -0,0 +1,183 @@
+// Apple localization catalogs - .strings files and .stringsdict plural plists
+//
+// .strings:      /* comment */ "key" = "value";   (// comments and unquoted
+//                keys allowed; \n \t \" \\ \Uxxxx escapes)
+// .stringsdict:  per key, an NSStringLocalizedFormatKey such as
+//                "%#@files@ left" and a variable dict of plural forms; each
+//                form is spliced into the format and its number specifier
+//                (%ld, %lu, ...) is rewritten to the %d count placeholder.
+//                As on Apple platforms, a "zero" form always covers 0.
+use super::xml::{self, Element};
+use super::{Catalog, PluralForms};
+
+pub(crate) fn parse_strings(text: &str) -> Result<Catalog, String> {
+    let mut catalog = Catalog::default();
+    let mut lexer = Lexer { text, pos: 0 };
+
+    while let Some(key) = lexer.token()? {
+        lexer.expect('=')?;
+        let value = lexer.token()?.ok_or_else(|| lexer.error("expected a value"))?;
+        lexer.expect(';')?;
+        catalog.strings.insert(key, value.into());
+    }
+    Ok(catalog)
+}
+
+/// Tokenizer for the old-style plist syntax used by .strings files
+struct Lexer<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl Lexer<'_> {
+    fn error(&self, message: &str) -> String {
+        let line = self.text[..self.pos].matches('\n').count() + 1;
+        format!("line {}: {}", line, message)
+    }
+
+    fn skip_trivia(&mut self) -> Result<(), String> {
+        loop {
+            let rest = &self.text[self.pos..];
+            let trimmed = rest.trim_start_matches(|c: char| c.is_whitespace() || c == '\u{feff}');
+            self.pos += rest.len() - trimmed.len();
+
+            if trimmed.starts_with("/*") {
+                let end = trimmed.find("*/").ok_or_else(|| self.error("unterminated comment"))?;
+                self.pos += end + 2;
+            } else if trimmed.starts_with("//") {
+                self.pos += trimmed.find('\n').unwrap_or(trimmed.len());
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), String> {
+        self.skip_trivia()?;
+        if !self.text[self.pos..].starts_with(c) {
+            return Err(self.error(&format!("expected '{}'", c)));
+        }
+        self.pos += c.len_utf8();
+        Ok(())
+    }
+
+    /// Next quoted or bare string, or None at the end of input
+    fn token(&mut self) -> Result<Option<String>, String> {
+        self.skip_trivia()?;
+        let rest = &self.text[self.pos..];
+        if rest.is_empty() {
+            return Ok(None);
+        }
+
+        if !rest.starts_with('"') {
+            let length = rest
+                .find(|c: char| !(c.is_alphanumeric() || "_.-:/$".contains(c)))
+                .unwrap_or(rest.len());
+            if length == 0 {
+                return Err(self.error("expected a string"));
+            }
+            self.pos += length;
+            return Ok(Some(rest[..length].to_string()));
+        }
+
+        let mut value = String::new();
+        let mut chars = rest.char_indices().skip(1);
+        while let Some((index, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.pos += index + 1;
+                    return Ok(Some(value));
+                }
+                '\\' => match chars.next().map(|(_, escaped)| escaped) {
+                    Some('n') => value.push('\n'),
+                    Some('t') => value.push('\t'),
+                    Some('r') => value.push('\r'),
+                    Some('U') | Some('u') => {
+                        let hex: String = chars.by_ref().take(4).map(|(_, h)| h).collect();
+                        let decoded = u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32);
+                        value.push(decoded.ok_or_else(|| self.error("invalid \\U escape"))?);
+                    }
+                    Some(other) => value.push(other),
+                    None => break,
+                },
+                _ => value.push(c),
+            }
+        }
+        Err(self.error("unterminated string"))
+    }
+}
+
+pub(crate) fn parse_stringsdict(text: &str) -> Result<Catalog, String> {
+    let root = xml::parse(text)?;
+    let dict = root.child("dict").ok_or("plist has no top-level dict")?;
+
+    let mut catalog = Catalog::default();
+    for (key, entry) in dict_entries(dict)? {
+        if entry.name != "dict" {
+            continue;
+        }
+        let entries = dict_entries(entry)?;
+        let format = entries
+            .iter()
+            .find(|(name, _)| name == "NSStringLocalizedFormatKey")
+            .map(|(_, value)| value.text())
+            .ok_or_else(|| format!("{} has no NSStringLocalizedFormatKey", key))?;
+
+        // The first %#@variable@ in the format selects the plural forms
+        let Some(variable) = format
+            .split_once("%#@")
+            .and_then(|(_, rest)| rest.split_once('@'))
+            .map(|(name, _)| name)
+        else {
+            catalog.strings.insert(key, format.into());
+            continue;
+        };
+        let rule = entries
+            .iter()
+            .find(|(name, value)| name == variable && value.name == "dict")
+            .map(|(_, value)| dict_entries(value))
+            .transpose()?
+            .ok_or_else(|| format!("{} has no {} rule", key, variable))?;
+
+        let spec = rule
+            .iter()
+            .find(|(name, _)| name == "NSStringFormatValueTypeKey")
+            .map(|(_, value)| format!("%{}", value.text()));
+        let placeholder = format!("%#@{}@", variable);
+        let mut forms: serde_json::Map<String, serde_json::Value> = rule
+            .iter()
+            .filter(|(name, _)| !name.starts_with("NSString"))
+            .map(|(category, value)| {
+                let mut form = value.text();
+                if let Some(spec) = spec.as_deref().filter(|spec| *spec != "%d") {
+                    form = form.replace(spec, "%d");
+                }
+                (category.clone(), format.replace(&placeholder, &form).into())
+            })
+            .collect();
+
+        // Apple uses "zero" for 0 even where CLDR has no zero category
+        if let Some(zero) = forms.get("zero").cloned() {
+            forms.insert("=0".to_string(), zero);
+        }
+        let forms = PluralForms::from_object(&forms).ok_or_else(|| format!("{} has no other form", key))?;
+        catalog.plurals.insert(key, forms);
+    }
+    Ok(catalog)
+}
+
+/// (key, value element) pairs of a plist <dict>
+fn dict_entries(dict: &Element) -> Result<Vec<(String, &Element)>, String> {
+    let mut entries = Vec::new();
+    let mut elements = dict.elements();
+    while let Some(key) = elements.next() {
+        if key.name != "key" {
+            return Err(format!("expected <key> in dict, found <{}>", key.name));
+        }
+        let value = elements
+            .next()
+            .ok_or_else(|| format!("dict key {} has no value", key.text()))?;
+        entries.push((key.text(), value));
+    }
+    Ok(entries)
+}
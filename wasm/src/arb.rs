@@ -0,0 +1,229 @@
+// ARB (Application Resource Bundle) catalogs - Flutter's JSON message format
+//
+// Plain messages load as strings. A message built around an ICU plural
+// ({count, plural, =0{..} one{..} other{..}}) becomes a plural block with
+// "#" and "{count}" rewritten to the %d count placeholder; text around the
+// argument is copied into every form. A select ({gender, select, male{..}
+// other{..}}) loads as "key" (the other case) plus "key@male" and so on.
+// "@key" metadata and "@@" attributes are skipped on import and regenerated
+// (placeholders only) on export.
+use std::collections::BTreeMap;
+
+use serde_json::{json, Value};
+
+use super::{Catalog, PluralCategory, PluralForms};
+
+/// Joins a message key and a select case: "greeting@male"
+pub(crate) const SELECT_SEPARATOR: char = '@';
+
+/// Placeholder names used on export, where the source argument names are gone
+const PLURAL_ARGUMENT: &str = "count";
+const SELECT_ARGUMENT: &str = "context";
+
+/// An ICU plural or select argument and the text around it
+struct Choice<'a> {
+    prefix: &'a str,
+    argument: &'a str,
+    kind: &'a str,
+    cases: Vec<(&'a str, &'a str)>,
+    suffix: &'a str,
+}
+
+pub(crate) fn parse(text: &str) -> Result<Catalog, String> {
+    let json: serde_json::Map<String, Value> = serde_json::from_str(text).map_err(|e| e.to_string())?;
+    let mut catalog = Catalog::default();
+
+    for (key, value) in &json {
+        if key.starts_with('@') {
+            continue;
+        }
+        let message = value
+            .as_str()
+            .ok_or_else(|| format!("{} is not a string", key))?;
+
+        let Some(choice) = parse_choice(message) else {
+            catalog.strings.insert(key.clone(), message.into());
+            continue;
+        };
+        if !choice.cases.iter().any(|(selector, _)| *selector == "other") {
+            return Err(format!("{} has no other case", key));
+        }
+
+        let wrap = |body: &str| format!("{}{}{}", choice.prefix, body, choice.suffix);
+        if choice.kind == "plural" {
+            let argument = format!("{{{}}}", choice.argument);
+            let forms: serde_json::Map<String, Value> = choice
+                .cases
+                .iter()
+                .map(|(selector, body)| {
+                    let body = body.replace('#', "%d").replace(&argument, "%d");
+                    (selector.to_string(), Value::String(wrap(&body)))
+                })
+                .collect();
+            if let Some(forms) = PluralForms::from_object(&forms) {
+                catalog.plurals.insert(key.clone(), forms);
+            }
+        } else {
+            for (selector, body) in &choice.cases {
+                let target = match *selector {
+                    "other" => key.clone(),
+                    case => format!("{}{}{}", key, SELECT_SEPARATOR, case),
+                };
+                catalog.strings.insert(target, wrap(body).into());
+            }
+        }
+    }
+
+    Ok(catalog)
+}
+
+/// Find the first top-level `{arg, plural|select, ...}` in a message
+fn parse_choice(message: &str) -> Option<Choice<'_>> {
+    let mut search = 0;
+    while let Some(open) = message[search..].find('{').map(|offset| search + offset) {
+        let close = matching_brace(message, open)?;
+        let mut parts = message[open + 1..close].splitn(3, ',');
+        if let (Some(argument), Some(kind), Some(body)) = (parts.next(), parts.next(), parts.next()) {
+            let kind = kind.trim();
+            if kind == "plural" || kind == "select" {
+                return Some(Choice {
+                    prefix: &message[..open],
+                    argument: argument.trim(),
+                    kind,
+                    cases: parse_cases(body)?,
+                    suffix: &message[close + 1..],
+                });
+            }
+        }
+        search = close + 1;
+    }
+    None
+}
+
+/// Parse `offset:1 =0{...} one{...} other{...}` into (selector, body) pairs
+fn parse_cases(body: &str) -> Option<Vec<(&str, &str)>> {
+    let mut rest = body.trim_start();
+    if let Some(after) = rest.strip_prefix("offset:") {
+        rest = after.trim_start().trim_start_matches(|c: char| c.is_ascii_digit()).trim_start();
+    }
+
+    let mut cases = Vec::new();
+    while !rest.is_empty() {
+        let open = rest.find('{')?;
+        let selector = rest[..open].trim();
+        let close = matching_brace(rest, open)?;
+        if selector.is_empty() {
+            return None;
+        }
+        cases.push((selector, &rest[open + 1..close]));
+        rest = rest[close + 1..].trim_start();
+    }
+    Some(cases)
+}
+
+fn matching_brace(text: &str, open: usize) -> Option<usize> {
+    let mut depth = 0;
+    for (index, byte) in text.bytes().enumerate().skip(open) {
+        match byte {
+            b'{' => depth += 1,
+            b'}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(index);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Write a catalog as ARB JSON: plural blocks become ICU plurals and
+/// "key@case" strings are folded back into a select on "key"
+pub(crate) fn write(catalog: &Catalog, locale: &str) -> String {
+    let mut messages: BTreeMap<&str, (String, Value)> = BTreeMap::new();
+
+    let mut selects: BTreeMap<&str, Vec<(&str, &str)>> = BTreeMap::new();
+    for (key, text) in &catalog.strings {
+        match key.rsplit_once(SELECT_SEPARATOR) {
+            Some((base, case)) if catalog.strings.contains_key(base) => {
+                selects.entry(base).or_default().push((case, text));
+            }
+            _ => {
+                let metadata = placeholder_metadata(placeholders(text).into_iter().map(|name| (name, "String")));
+                messages.insert(key, (text.to_string(), metadata));
+            }
+        }
+    }
+
+    for (key, mut cases) in selects {
+        cases.sort_unstable();
+        let mut message = format!("{{{}, select,", SELECT_ARGUMENT);
+        for (case, text) in cases {
+            message.push_str(&format!(" {}{{{}}}", case, text));
+        }
+        message.push_str(&format!(" other{{{}}}}}", catalog.strings[key]));
+        messages.insert(key, (message, placeholder_metadata([(SELECT_ARGUMENT.to_string(), "String")])));
+    }
+
+    for (key, forms) in &catalog.plurals {
+        let argument = format!("{{{}}}", PLURAL_ARGUMENT);
+        let mut message = format!("{{{}, plural,", PLURAL_ARGUMENT);
+        for (exact, text) in &forms.exact {
+            message.push_str(&format!(" ={}{{{}}}", exact, text.replace("%d", &argument)));
+        }
+        for category in [
+            PluralCategory::Zero,
+            PluralCategory::One,
+            PluralCategory::Two,
+            PluralCategory::Few,
+            PluralCategory::Many,
+            PluralCategory::Other,
+        ] {
+            if let Some(text) = forms.get(category) {
+                message.push_str(&format!(" {}{{{}}}", category.as_str(), text.replace("%d", &argument)));
+            }
+        }
+        message.push('}');
+        messages.insert(key, (message, placeholder_metadata([(PLURAL_ARGUMENT.to_string(), "num")])));
+    }
+
+    let mut out = format!("{{\n  \"@@locale\": {}", Value::String(locale.to_string()));
+    for (key, (message, metadata)) in messages {
+        out.push_str(&format!(",\n  {}: {}", Value::String(key.to_string()), Value::String(message)));
+        if !metadata.is_null() {
+            out.push_str(&format!(",\n  {}: {}", Value::String(format!("@{}", key)), metadata));
+        }
+    }
+    out.push_str("\n}\n");
+    out
+}
+
+/// Simple `{name}` placeholders in a message, in order of first use
+fn placeholders(text: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    let mut rest = text;
+    while let Some(open) = rest.find('{') {
+        rest = &rest[open + 1..];
+        let Some(close) = rest.find('}') else { break };
+        let name = &rest[..close];
+        let is_identifier = !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_');
+        if is_identifier && !names.iter().any(|existing| existing == name) {
+            names.push(name.to_string());
+        }
+    }
+    names
+}
+
+/// `{"placeholders": {...}}` for the given (name, type) pairs, or null if none
+fn placeholder_metadata(placeholders: impl IntoIterator<Item = (String, &'static str)>) -> Value {
+    let placeholders: serde_json::Map<String, Value> = placeholders
+        .into_iter()
+        .map(|(name, kind)| (name, json!({ "type": kind })))
+        .collect();
+    if placeholders.is_empty() {
+        Value::Null
+    } else {
+        json!({ "placeholders": placeholders })
+    }
+}
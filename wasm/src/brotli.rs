@@ -0,0 +1,705 @@
+// Brotli decompression for compressed catalogs
+//
+// A decoder for RFC 7932 streams, HTTP's "br" Content-Encoding: meta-block
+// headers, simple and complex prefix codes, block switching, context maps
+// and the static dictionary with its 121 word transforms. Prefix codes use
+// inflate's canonical Huffman decoder, since the two formats build them the
+// same way. The whole output is kept, so the sliding window never needs
+// trimming. Built with the `brotli` feature, which embeds the 120 KiB
+// dictionary.
+
+use crate::inflate::{Bits, Huffman};
+
+/// RFC 7932 appendix A, as shipped with the reference decoder
+static DICTIONARY: &[u8; 122_784] = include_bytes!("../data/brotli-dictionary.bin");
+/// log2 of the number of dictionary words of each length 4..24
+const DICTIONARY_SIZE_BITS: [u8; 25] = [0, 0, 0, 0, 10, 10, 11, 11, 10, 10, 10, 10, 10, 9, 9, 8, 7, 7, 8, 7, 7, 6, 6, 5, 5];
+/// Where the words of each length start in the dictionary
+const DICTIONARY_OFFSETS: [u32; 25] = [
+    0, 0, 0, 0, 0, 4096, 9216, 21504, 35840, 44032, 53248, 63488, 74752, 87040, 93696, 100864, 104704, 106752, 108928,
+    113536, 115968, 118528, 119872, 121280, 122016,
+];
+
+/// Insert length codes 0..23: base length and extra bits
+const INSERT_BASE: [u32; 24] = [
+    0, 1, 2, 3, 4, 5, 6, 8, 10, 14, 18, 26, 34, 50, 66, 98, 130, 194, 322, 578, 1090, 2114, 6210, 22594,
+];
+const INSERT_EXTRA: [u8; 24] = [0, 0, 0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 7, 8, 9, 10, 12, 14, 24];
+/// Copy length codes 0..23: base length and extra bits
+const COPY_BASE: [u32; 24] = [
+    2, 3, 4, 5, 6, 7, 8, 9, 10, 12, 14, 18, 22, 30, 38, 54, 70, 102, 134, 198, 326, 582, 1094, 2118,
+];
+const COPY_EXTRA: [u8; 24] = [0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 7, 8, 9, 10, 24];
+/// Insert-and-copy symbols come in cells of 64: the insert and copy code
+/// each cell starts at, and whether it implies distance code 0
+const COMMAND_CELLS: [(u8, u8, bool); 11] = [
+    (0, 0, true),
+    (0, 8, true),
+    (0, 0, false),
+    (0, 8, false),
+    (8, 0, false),
+    (8, 8, false),
+    (0, 16, false),
+    (16, 0, false),
+    (8, 16, false),
+    (16, 8, false),
+    (16, 16, false),
+];
+/// Block count codes 0..25: base count and extra bits
+const BLOCK_COUNT_BASE: [u32; 26] = [
+    1, 5, 9, 13, 17, 25, 33, 41, 49, 65, 81, 97, 113, 145, 177, 209, 241, 305, 369, 497, 753, 1265, 2289, 4337, 8433,
+    16625,
+];
+const BLOCK_COUNT_EXTRA: [u8; 26] = [2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 6, 6, 7, 8, 9, 10, 11, 12, 13, 24];
+/// Order in which complex prefix codes list code length code lengths
+const CODE_LENGTH_ORDER: [usize; 18] = [1, 2, 3, 4, 0, 5, 17, 6, 16, 7, 8, 9, 10, 11, 12, 13, 14, 15];
+/// Distance codes 0..15 relative to the last four distances: which one
+/// (0 is the most recent) and the adjustment
+const DISTANCE_SHORT_CODES: [(usize, i32); 16] = [
+    (0, 0),
+    (1, 0),
+    (2, 0),
+    (3, 0),
+    (0, -1),
+    (0, 1),
+    (0, -2),
+    (0, 2),
+    (0, -3),
+    (0, 3),
+    (1, -1),
+    (1, 1),
+    (1, -2),
+    (1, 2),
+    (1, -3),
+    (1, 3),
+];
+
+/// Word transforms: prefix, kind and suffix. Kind 0 keeps the word, 1..=9
+/// drop that many bytes from the end, 10 uppercases the first letter, 11
+/// every letter, and 12..=20 drop 1..=9 bytes from the start
+const TRANSFORMS: [(&[u8], u8, &[u8]); 121] = [
+    (b"", 0, b""),
+    (b"", 0, b" "),
+    (b" ", 0, b" "),
+    (b"", 12, b""),
+    (b"", 10, b" "),
+    (b"", 0, b" the "),
+    (b" ", 0, b""),
+    (b"s ", 0, b" "),
+    (b"", 0, b" of "),
+    (b"", 10, b""),
+    (b"", 0, b" and "),
+    (b"", 13, b""),
+    (b"", 1, b""),
+    (b", ", 0, b" "),
+    (b"", 0, b", "),
+    (b" ", 10, b" "),
+    (b"", 0, b" in "),
+    (b"", 0, b" to "),
+    (b"e ", 0, b" "),
+    (b"", 0, b"\""),
+    (b"", 0, b"."),
+    (b"", 0, b"\">"),
+    (b"", 0, b"\x0a"),
+    (b"", 3, b""),
+    (b"", 0, b"]"),
+    (b"", 0, b" for "),
+    (b"", 14, b""),
+    (b"", 2, b""),
+    (b"", 0, b" a "),
+    (b"", 0, b" that "),
+    (b" ", 10, b""),
+    (b"", 0, b". "),
+    (b".", 0, b""),
+    (b" ", 0, b", "),
+    (b"", 15, b""),
+    (b"", 0, b" with "),
+    (b"", 0, b"'"),
+    (b"", 0, b" from "),
+    (b"", 0, b" by "),
+    (b"", 16, b""),
+    (b"", 17, b""),
+    (b" the ", 0, b""),
+    (b"", 4, b""),
+    (b"", 0, b". The "),
+    (b"", 11, b""),
+    (b"", 0, b" on "),
+    (b"", 0, b" as "),
+    (b"", 0, b" is "),
+    (b"", 7, b""),
+    (b"", 1, b"ing "),
+    (b"", 0, b"\x0a\x09"),
+    (b"", 0, b":"),
+    (b" ", 0, b". "),
+    (b"", 0, b"ed "),
+    (b"", 20, b""),
+    (b"", 18, b""),
+    (b"", 6, b""),
+    (b"", 0, b"("),
+    (b"", 10, b", "),
+    (b"", 8, b""),
+    (b"", 0, b" at "),
+    (b"", 0, b"ly "),
+    (b" the ", 0, b" of "),
+    (b"", 5, b""),
+    (b"", 9, b""),
+    (b" ", 10, b", "),
+    (b"", 10, b"\""),
+    (b".", 0, b"("),
+    (b"", 11, b" "),
+    (b"", 10, b"\">"),
+    (b"", 0, b"=\""),
+    (b" ", 0, b"."),
+    (b".com/", 0, b""),
+    (b" the ", 0, b" of the "),
+    (b"", 10, b"'"),
+    (b"", 0, b". This "),
+    (b"", 0, b","),
+    (b".", 0, b" "),
+    (b"", 10, b"("),
+    (b"", 10, b"."),
+    (b"", 0, b" not "),
+    (b" ", 0, b"=\""),
+    (b"", 0, b"er "),
+    (b" ", 11, b" "),
+    (b"", 0, b"al "),
+    (b" ", 11, b""),
+    (b"", 0, b"='"),
+    (b"", 11, b"\""),
+    (b"", 10, b". "),
+    (b" ", 0, b"("),
+    (b"", 0, b"ful "),
+    (b" ", 10, b". "),
+    (b"", 0, b"ive "),
+    (b"", 0, b"less "),
+    (b"", 11, b"'"),
+    (b"", 0, b"est "),
+    (b" ", 10, b"."),
+    (b"", 11, b"\">"),
+    (b" ", 0, b"='"),
+    (b"", 10, b","),
+    (b"", 0, b"ize "),
+    (b"", 11, b"."),
+    (b"\xc2\xa0", 0, b""),
+    (b" ", 0, b","),
+    (b"", 10, b"=\""),
+    (b"", 11, b"=\""),
+    (b"", 0, b"ous "),
+    (b"", 11, b", "),
+    (b"", 10, b"='"),
+    (b" ", 10, b","),
+    (b" ", 11, b"=\""),
+    (b" ", 11, b", "),
+    (b"", 11, b","),
+    (b"", 11, b"("),
+    (b"", 11, b". "),
+    (b" ", 11, b"."),
+    (b"", 11, b"='"),
+    (b" ", 11, b". "),
+    (b" ", 10, b"=\""),
+    (b" ", 11, b"='"),
+    (b" ", 10, b"='"),
+];
+
+/// Literal context lookups for UTF-8 mode, by the last and second-to-last byte
+const UTF8_LUT0: [u8; 256] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 4, 4, 0, 0, 4, 0, 0,
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    8, 12, 16, 12, 12, 20, 12, 16, 24, 28, 12, 12, 32, 12, 36, 12,
+    44, 44, 44, 44, 44, 44, 44, 44, 44, 44, 32, 32, 24, 40, 28, 12,
+    12, 48, 52, 52, 52, 48, 52, 52, 52, 48, 52, 52, 52, 52, 52, 48,
+    52, 52, 52, 52, 52, 48, 52, 52, 52, 52, 52, 24, 12, 28, 12, 12,
+    12, 56, 60, 60, 60, 56, 60, 60, 60, 56, 60, 60, 60, 60, 60, 56,
+    60, 60, 60, 60, 60, 56, 60, 60, 60, 60, 60, 24, 12, 28, 12, 0,
+    0, 1, 0, 1, 0, 1, 0, 1, 0, 1, 0, 1, 0, 1, 0, 1,
+    0, 1, 0, 1, 0, 1, 0, 1, 0, 1, 0, 1, 0, 1, 0, 1,
+    0, 1, 0, 1, 0, 1, 0, 1, 0, 1, 0, 1, 0, 1, 0, 1,
+    0, 1, 0, 1, 0, 1, 0, 1, 0, 1, 0, 1, 0, 1, 0, 1,
+    2, 3, 2, 3, 2, 3, 2, 3, 2, 3, 2, 3, 2, 3, 2, 3,
+    2, 3, 2, 3, 2, 3, 2, 3, 2, 3, 2, 3, 2, 3, 2, 3,
+    2, 3, 2, 3, 2, 3, 2, 3, 2, 3, 2, 3, 2, 3, 2, 3,
+    2, 3, 2, 3, 2, 3, 2, 3, 2, 3, 2, 3, 2, 3, 2, 3,
+];
+const UTF8_LUT1: [u8; 256] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    0, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1,
+    2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 1, 1, 1, 1, 1, 1,
+    1, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2,
+    2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 1, 1, 1, 1, 1,
+    1, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3,
+    3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 1, 1, 1, 1, 0,
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2,
+    2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2,
+];
+/// Literal context lookup for signed mode, applied to both bytes
+const SIGNED_LUT: [u8; 256] = [
+    0, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1,
+    2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2,
+    2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2,
+    2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2,
+    3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3,
+    3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3,
+    3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3,
+    3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3,
+    4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4,
+    4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4,
+    4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4,
+    4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4,
+    5, 5, 5, 5, 5, 5, 5, 5, 5, 5, 5, 5, 5, 5, 5, 5,
+    5, 5, 5, 5, 5, 5, 5, 5, 5, 5, 5, 5, 5, 5, 5, 5,
+    5, 5, 5, 5, 5, 5, 5, 5, 5, 5, 5, 5, 5, 5, 5, 5,
+    6, 6, 6, 6, 6, 6, 6, 6, 6, 6, 6, 6, 6, 6, 6, 7,
+];
+
+/// Decompress a brotli stream
+pub(crate) fn unbrotli(bytes: &[u8]) -> Result<Vec<u8>, String> {
+    let mut input = Bits::new(bytes);
+    let window = window_size(&mut input)?;
+    let mut out = Vec::with_capacity(bytes.len() * 4);
+    // The last four distances, most recent first; they carry across
+    // meta-blocks
+    let mut distances = [4, 11, 15, 16];
+    loop {
+        let last = input.bits(1)? == 1;
+        if last && input.bits(1)? == 1 {
+            return Ok(out);
+        }
+        let nibbles = input.bits(2)? as u8;
+        if nibbles == 3 {
+            // Metadata: skipped
+            if input.bits(1)? != 0 {
+                return Err("reserved bit set".to_string());
+            }
+            let skip_bytes = input.bits(2)?;
+            let mut length = 0;
+            for index in 0..skip_bytes {
+                length |= (input.bits(8)? as usize) << (8 * index);
+            }
+            if skip_bytes > 0 {
+                length += 1;
+            }
+            input.align();
+            input.bytes(length)?;
+        } else {
+            let length = input.bits(4 * (nibbles + 4))? as usize + 1;
+            if !last && input.bits(1)? == 1 {
+                // Uncompressed meta-blocks start on a byte boundary
+                input.align();
+                out.extend_from_slice(input.bytes(length)?);
+            } else {
+                meta_block(&mut input, &mut out, length, window, &mut distances)?;
+            }
+        }
+        if last {
+            return Ok(out);
+        }
+    }
+}
+
+/// Window size from the stream header; distances beyond it (or beyond the
+/// output so far) refer to the dictionary
+fn window_size(input: &mut Bits) -> Result<usize, String> {
+    let bits = if input.bits(1)? == 0 {
+        16
+    } else {
+        match input.bits(3)? {
+            0 => match input.bits(3)? {
+                0 => 17,
+                1 => return Err("large windows are not supported".to_string()),
+                bits => 8 + bits,
+            },
+            bits => 17 + bits,
+        }
+    };
+    Ok((1 << bits) - 16)
+}
+
+fn meta_block(
+    input: &mut Bits,
+    out: &mut Vec<u8>,
+    length: usize,
+    window: usize,
+    distances: &mut [usize; 4],
+) -> Result<(), String> {
+    let mut literal_blocks = Blocks::read(input)?;
+    let mut command_blocks = Blocks::read(input)?;
+    let mut distance_blocks = Blocks::read(input)?;
+    let postfix_bits = input.bits(2)? as u8;
+    let direct = (input.bits(4)? as usize) << postfix_bits;
+    let modes = (0..literal_blocks.types).map(|_| input.bits(2).map(|mode| mode as u8)).collect::<Result<Vec<_>, _>>()?;
+    let literal_trees = var_len(input)? + 1;
+    let literal_map = context_map(input, 64 * literal_blocks.types, literal_trees)?;
+    let distance_trees = var_len(input)? + 1;
+    let distance_map = context_map(input, 4 * distance_blocks.types, distance_trees)?;
+    let literal_codes = codes(input, literal_trees, 256)?;
+    let command_codes = codes(input, command_blocks.types, 704)?;
+    let distance_codes = codes(input, distance_trees, 16 + direct + (48 << postfix_bits))?;
+
+    let end = out.len() + length;
+    while out.len() < end {
+        let command = command_codes[command_blocks.next(input)?].decode(input)? as usize;
+        let (insert_cell, copy_cell, implicit_distance) = COMMAND_CELLS[command >> 6];
+        let insert_code = insert_cell as usize + ((command >> 3) & 7);
+        let copy_code = copy_cell as usize + (command & 7);
+        let insert = INSERT_BASE[insert_code] as usize + input.bits(INSERT_EXTRA[insert_code])? as usize;
+        let copy = COPY_BASE[copy_code] as usize + input.bits(COPY_EXTRA[copy_code])? as usize;
+
+        for _ in 0..insert {
+            let block = literal_blocks.next(input)?;
+            let p1 = out.last().copied().unwrap_or(0);
+            let p2 = out.len().checked_sub(2).map_or(0, |index| out[index]);
+            let tree = literal_map[64 * block + literal_context(modes[block], p1, p2)] as usize;
+            let literal = literal_codes.get(tree).ok_or("invalid context map")?.decode(input)?;
+            out.push(literal as u8);
+            // A meta-block may end after the literals, dropping the copy
+            if out.len() == end {
+                return Ok(());
+            }
+        }
+
+        let distance_code = if implicit_distance {
+            0
+        } else {
+            let block = distance_blocks.next(input)?;
+            let tree = distance_map[4 * block + copy.min(5) - 2] as usize;
+            distance_codes.get(tree).ok_or("invalid context map")?.decode(input)? as usize
+        };
+        let distance = distance(input, distance_code, distances, postfix_bits, direct)?;
+        let max_distance = out.len().min(window);
+        if distance > max_distance {
+            let word = dictionary_word(copy, distance - max_distance - 1)?;
+            if out.len() + word.len() > end {
+                return Err("dictionary word past end of meta-block".to_string());
+            }
+            out.extend_from_slice(&word);
+        } else {
+            if distance_code != 0 {
+                distances.rotate_right(1);
+                distances[0] = distance;
+            }
+            if out.len() + copy > end {
+                return Err("copy past end of meta-block".to_string());
+            }
+            // Copies may overlap what they write, as in DEFLATE
+            let start = out.len() - distance;
+            for index in start..start + copy {
+                out.push(out[index]);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Block switching for one category (literals, commands or distances): how
+/// many block types there are, the current and previous type, and how many
+/// symbols are left in the current block
+struct Blocks {
+    types: usize,
+    codes: Option<(Code, Code)>,
+    current: usize,
+    previous: usize,
+    remaining: usize,
+}
+
+impl Blocks {
+    fn read(input: &mut Bits) -> Result<Blocks, String> {
+        let types = var_len(input)? + 1;
+        if types == 1 {
+            return Ok(Blocks { types, codes: None, current: 0, previous: 1, remaining: usize::MAX });
+        }
+        let type_code = Code::read(input, types + 2)?;
+        let count_code = Code::read(input, 26)?;
+        let remaining = block_count(input, &count_code)?;
+        Ok(Blocks { types, codes: Some((type_code, count_code)), current: 0, previous: 1, remaining })
+    }
+
+    /// The block type of the next symbol, switching blocks when the current
+    /// one runs out
+    fn next(&mut self, input: &mut Bits) -> Result<usize, String> {
+        if self.remaining == 0 {
+            let (type_code, count_code) = self.codes.as_ref().ok_or("block count overflow")?;
+            let next = match type_code.decode(input)? {
+                0 => self.previous,
+                1 => self.current + 1,
+                code => code as usize - 2,
+            } % self.types;
+            self.previous = self.current;
+            self.current = next;
+            self.remaining = block_count(input, count_code)?;
+        }
+        self.remaining -= 1;
+        Ok(self.current)
+    }
+}
+
+fn block_count(input: &mut Bits, code: &Code) -> Result<usize, String> {
+    let symbol = code.decode(input)? as usize;
+    Ok(BLOCK_COUNT_BASE[symbol] as usize + input.bits(BLOCK_COUNT_EXTRA[symbol])? as usize)
+}
+
+/// A prefix code; a code with a single symbol takes no bits
+enum Code {
+    Single(u16),
+    Huffman(Huffman),
+}
+
+impl Code {
+    fn read(input: &mut Bits, alphabet: usize) -> Result<Code, String> {
+        let mut lengths = vec![0u8; alphabet];
+        let skip = input.bits(2)? as usize;
+        if skip == 1 {
+            // Simple code: one to four symbols with implied lengths
+            let width = (usize::BITS - (alphabet - 1).leading_zeros()) as u8;
+            let count = input.bits(2)? as usize + 1;
+            let mut symbols = [0; 4];
+            for symbol in &mut symbols[..count] {
+                *symbol = input.bits(width)? as usize;
+                if *symbol >= alphabet {
+                    return Err("invalid prefix code symbol".to_string());
+                }
+            }
+            let implied: &[u8] = match count {
+                1 => &[1],
+                2 => &[1, 1],
+                3 => &[1, 2, 2],
+                _ if input.bits(1)? == 0 => &[2, 2, 2, 2],
+                _ => &[1, 2, 3, 3],
+            };
+            for (&symbol, &length) in symbols.iter().zip(implied) {
+                if lengths[symbol] != 0 {
+                    return Err("repeated prefix code symbol".to_string());
+                }
+                lengths[symbol] = length;
+            }
+            return Code::from_lengths(&lengths);
+        }
+
+        // Complex code: the code lengths are themselves prefix coded, by a
+        // code whose lengths come first
+        let mut code_lengths = [0u8; 18];
+        let (mut space, mut used) = (32, 0);
+        for &symbol in &CODE_LENGTH_ORDER[skip..] {
+            let length = code_length_length(input)?;
+            code_lengths[symbol] = length;
+            if length != 0 {
+                space -= 32 >> length;
+                used += 1;
+                if space <= 0 {
+                    break;
+                }
+            }
+        }
+        if space != 0 && used != 1 {
+            return Err("invalid code length code".to_string());
+        }
+        let code_length_code = Code::from_lengths(&code_lengths)?;
+
+        let (mut space, mut symbol) = (32768, 0);
+        // 16 repeats the last non-zero length, 17 repeats zero; consecutive
+        // repeats of the same length extend the previous run
+        let (mut previous, mut repeat, mut repeat_length) = (8, 0, 0);
+        while symbol < alphabet && space > 0 {
+            let code = code_length_code.decode(input)? as u8;
+            if code < 16 {
+                repeat = 0;
+                lengths[symbol] = code;
+                if code != 0 {
+                    previous = code;
+                    space -= 32768 >> code;
+                }
+                symbol += 1;
+                continue;
+            }
+            let (length, extra) = if code == 16 { (previous, 2) } else { (0, 3) };
+            if repeat_length != length {
+                repeat = 0;
+                repeat_length = length;
+            }
+            let old = repeat;
+            if repeat > 0 {
+                repeat = (repeat - 2) << extra;
+            }
+            repeat += input.bits(extra)? as usize + 3;
+            let added = repeat - old;
+            lengths.get_mut(symbol..symbol + added).ok_or("too many code lengths")?.fill(length);
+            if length != 0 {
+                space -= (added as i32) << (15 - length);
+            }
+            symbol += added;
+        }
+        if space != 0 {
+            return Err("incomplete prefix code".to_string());
+        }
+        Code::from_lengths(&lengths)
+    }
+
+    fn from_lengths(lengths: &[u8]) -> Result<Code, String> {
+        let mut symbols = lengths.iter().enumerate().filter(|(_, length)| **length != 0);
+        match (symbols.next(), symbols.next()) {
+            (Some((symbol, _)), None) => Ok(Code::Single(symbol as u16)),
+            _ => Huffman::new(lengths).map(Code::Huffman),
+        }
+    }
+
+    fn decode(&self, input: &mut Bits) -> Result<u16, String> {
+        match self {
+            Code::Single(symbol) => Ok(*symbol),
+            Code::Huffman(huffman) => huffman.decode(input),
+        }
+    }
+}
+
+/// The fixed code for code length code lengths: 00, 10 and 01 (as read)
+/// are 0, 3 and 4; 110, 1110 and 1111 are 2, 1 and 5
+fn code_length_length(input: &mut Bits) -> Result<u8, String> {
+    Ok(match input.bits(2)? {
+        0 => 0,
+        1 => 4,
+        2 => 3,
+        _ if input.bits(1)? == 0 => 2,
+        _ if input.bits(1)? == 0 => 1,
+        _ => 5,
+    })
+}
+
+fn codes(input: &mut Bits, count: usize, alphabet: usize) -> Result<Vec<Code>, String> {
+    (0..count).map(|_| Code::read(input, alphabet)).collect()
+}
+
+/// VarLenUint8: 0..=255 in one to eleven bits
+fn var_len(input: &mut Bits) -> Result<usize, String> {
+    if input.bits(1)? == 0 {
+        return Ok(0);
+    }
+    Ok(match input.bits(3)? as u8 {
+        0 => 1,
+        bits => (1 << bits) + input.bits(bits)? as usize,
+    })
+}
+
+/// Which prefix code each block type and context uses: run-length coded
+/// zeros, optionally move-to-front transformed
+fn context_map(input: &mut Bits, size: usize, trees: usize) -> Result<Vec<u8>, String> {
+    let mut map = vec![0u8; size];
+    if trees == 1 {
+        return Ok(map);
+    }
+    let max_run = if input.bits(1)? == 1 { input.bits(4)? as usize + 1 } else { 0 };
+    let code = Code::read(input, trees + max_run)?;
+    let mut index = 0;
+    while index < size {
+        match code.decode(input)? as usize {
+            0 => index += 1,
+            run if run <= max_run => {
+                index += (1 << run) + input.bits(run as u8)? as usize;
+                if index > size {
+                    return Err("context map overflow".to_string());
+                }
+            }
+            tree => {
+                map[index] = (tree - max_run) as u8;
+                index += 1;
+            }
+        }
+    }
+    if input.bits(1)? == 1 {
+        let mut order: Vec<u8> = (0..=255).collect();
+        for value in &mut map {
+            let entry = order.remove(*value as usize);
+            order.insert(0, entry);
+            *value = entry;
+        }
+    }
+    Ok(map)
+}
+
+/// Literal context (0..63) from the last two output bytes
+fn literal_context(mode: u8, p1: u8, p2: u8) -> usize {
+    (match mode {
+        0 => p1 & 0x3f,
+        1 => p1 >> 2,
+        2 => UTF8_LUT0[p1 as usize] | UTF8_LUT1[p2 as usize],
+        _ => (SIGNED_LUT[p1 as usize] << 3) | SIGNED_LUT[p2 as usize],
+    }) as usize
+}
+
+fn distance(
+    input: &mut Bits,
+    code: usize,
+    distances: &[usize; 4],
+    postfix_bits: u8,
+    direct: usize,
+) -> Result<usize, String> {
+    if let Some(&(which, delta)) = DISTANCE_SHORT_CODES.get(code) {
+        return match distances[which] as i64 + delta as i64 {
+            distance if distance > 0 => Ok(distance as usize),
+            _ => Err("invalid distance".to_string()),
+        };
+    }
+    if code < 16 + direct {
+        return Ok(code - 15);
+    }
+    let code = code - 16 - direct;
+    let extra_bits = 1 + (code >> (postfix_bits + 1)) as u8;
+    let offset = ((2 + ((code >> postfix_bits) & 1)) << extra_bits) - 4;
+    let postfix = code & ((1 << postfix_bits) - 1);
+    Ok(((offset + input.bits(extra_bits)? as usize) << postfix_bits) + postfix + direct + 1)
+}
+
+/// The dictionary word a reference past the window stands for: `id` picks
+/// the word among those of its length and, above that, the transform
+fn dictionary_word(length: usize, id: usize) -> Result<Vec<u8>, String> {
+    let size_bits = *DICTIONARY_SIZE_BITS.get(length).filter(|bits| **bits != 0).ok_or("invalid dictionary reference")?;
+    let &(prefix, kind, suffix) = TRANSFORMS.get(id >> size_bits).ok_or("invalid dictionary transform")?;
+    let start = DICTIONARY_OFFSETS[length] as usize + (id & ((1 << size_bits) - 1)) * length;
+    let mut word = DICTIONARY[start..start + length].to_vec();
+    match kind {
+        1..=9 => word.truncate(length.saturating_sub(kind as usize)),
+        10 => {
+            uppercase(&mut word);
+        }
+        11 => {
+            let mut index = 0;
+            while index < word.len() {
+                index += uppercase(&mut word[index..]);
+            }
+        }
+        12..=20 => {
+            word.drain(..(kind as usize - 11).min(length));
+        }
+        _ => {}
+    }
+    Ok([prefix, &word, suffix].concat())
+}
+
+/// Uppercase the character at the start of `word` as the reference decoder
+/// does (ASCII letters, and a fixed bit flip for longer UTF-8 sequences);
+/// returns the character's length
+fn uppercase(word: &mut [u8]) -> usize {
+    match word[0] {
+        b'a'..=b'z' => {
+            word[0] ^= 32;
+            1
+        }
+        0..=0xbf => 1,
+        0xc0..=0xdf => {
+            if let Some(byte) = word.get_mut(1) {
+                *byte ^= 32;
+            }
+            2
+        }
+        _ => {
+            if let Some(byte) = word.get_mut(2) {
+                *byte ^= 5;
+            }
+            3
+        }
+    }
+}
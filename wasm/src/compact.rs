@@ -0,0 +1,145 @@
+// Compact decimal patterns for NumberFormat's compact notation
+//
+// Each range starts at a magnitude (the exponent of the number's leading
+// digit) and divides by a power of ten, as CLDR's decimalFormats-short and
+// -long do: "0K" from 10^3 divides by 10^3, so 12345 is "12K". A range runs
+// until the next one, so es writes 10^9 as "1000 M" until "mil M" at 10^10,
+// and magnitudes below the first range are not compacted (de has no short
+// thousands, ja and zh count in 10^4). Forms are keyed by plural category
+// with "other" as the fallback; "0" in a form stands for the number, and a
+// form without it (it "mille") replaces the number. The data follows CLDR
+// for the locales below; any other locale uses English.
+use super::canonicalize_locale;
+
+/// (first magnitude, divisor exponent, forms by plural category)
+pub(crate) type Range = (u32, u32, &'static [(&'static str, &'static str)]);
+
+/// (locale, short ranges, long ranges)
+const PATTERNS: &[(&str, &[Range], &[Range])] = &[
+    (
+        "en",
+        &[(3, 3, &[("other", "0K")]), (6, 6, &[("other", "0M")]), (9, 9, &[("other", "0B")]), (12, 12, &[("other", "0T")])],
+        &[
+            (3, 3, &[("other", "0 thousand")]),
+            (6, 6, &[("other", "0 million")]),
+            (9, 9, &[("other", "0 billion")]),
+            (12, 12, &[("other", "0 trillion")]),
+        ],
+    ),
+    (
+        "de",
+        &[(6, 6, &[("other", "0 Mio.")]), (9, 9, &[("other", "0 Mrd.")]), (12, 12, &[("other", "0 Bio.")])],
+        &[
+            (3, 3, &[("other", "0 Tausend")]),
+            (6, 6, &[("one", "0 Million"), ("other", "0 Millionen")]),
+            (9, 9, &[("one", "0 Milliarde"), ("other", "0 Milliarden")]),
+            (12, 12, &[("one", "0 Billion"), ("other", "0 Billionen")]),
+        ],
+    ),
+    (
+        "es",
+        &[
+            (3, 3, &[("other", "0 mil")]),
+            (6, 6, &[("other", "0 M")]),
+            (10, 9, &[("other", "0 mil M")]),
+            (12, 12, &[("other", "0 B")]),
+        ],
+        &[
+            (3, 3, &[("other", "0 mil")]),
+            (6, 6, &[("one", "0 millón"), ("other", "0 millones")]),
+            (9, 9, &[("other", "0 mil millones")]),
+            (12, 12, &[("one", "0 billón"), ("other", "0 billones")]),
+        ],
+    ),
+    (
+        "fr",
+        &[(3, 3, &[("other", "0 k")]), (6, 6, &[("other", "0 M")]), (9, 9, &[("other", "0 Md")]), (12, 12, &[("other", "0 Bn")])],
+        &[
+            (3, 3, &[("one", "0 millier"), ("other", "0 mille")]),
+            (6, 6, &[("one", "0 million"), ("other", "0 millions")]),
+            (9, 9, &[("one", "0 milliard"), ("other", "0 milliards")]),
+            (12, 12, &[("one", "0 billion"), ("other", "0 billions")]),
+        ],
+    ),
+    (
+        "it",
+        &[(6, 6, &[("other", "0 Mln")]), (9, 9, &[("other", "0 Mrd")]), (12, 12, &[("other", "0 Bln")])],
+        &[
+            (3, 3, &[("one", "mille"), ("other", "0 mila")]),
+            (6, 6, &[("one", "0 milione"), ("other", "0 milioni")]),
+            (9, 9, &[("one", "0 miliardo"), ("other", "0 miliardi")]),
+            (12, 12, &[("one", "0 mille miliardi"), ("other", "0 mila miliardi")]),
+        ],
+    ),
+    (
+        "ja",
+        &[(4, 4, &[("other", "0万")]), (8, 8, &[("other", "0億")]), (12, 12, &[("other", "0兆")])],
+        &[(4, 4, &[("other", "0万")]), (8, 8, &[("other", "0億")]), (12, 12, &[("other", "0兆")])],
+    ),
+    (
+        "nl",
+        &[(3, 3, &[("other", "0K")]), (6, 6, &[("other", "0 mln.")]), (9, 9, &[("other", "0 mld.")]), (12, 12, &[("other", "0 bln.")])],
+        &[
+            (3, 3, &[("other", "0 duizend")]),
+            (6, 6, &[("other", "0 miljoen")]),
+            (9, 9, &[("other", "0 miljard")]),
+            (12, 12, &[("other", "0 biljoen")]),
+        ],
+    ),
+    (
+        "pt",
+        &[(3, 3, &[("other", "0 mil")]), (6, 6, &[("other", "0 mi")]), (9, 9, &[("other", "0 bi")]), (12, 12, &[("other", "0 tri")])],
+        &[
+            (3, 3, &[("other", "0 mil")]),
+            (6, 6, &[("one", "0 milhão"), ("other", "0 milhões")]),
+            (9, 9, &[("one", "0 bilhão"), ("other", "0 bilhões")]),
+            (12, 12, &[("one", "0 trilhão"), ("other", "0 trilhões")]),
+        ],
+    ),
+    (
+        "ru",
+        &[(3, 3, &[("other", "0 тыс.")]), (6, 6, &[("other", "0 млн")]), (9, 9, &[("other", "0 млрд")]), (12, 12, &[("other", "0 трлн")])],
+        &[
+            (3, 3, &[("one", "0 тысяча"), ("few", "0 тысячи"), ("many", "0 тысяч"), ("other", "0 тысячи")]),
+            (6, 6, &[("one", "0 миллион"), ("few", "0 миллиона"), ("many", "0 миллионов"), ("other", "0 миллиона")]),
+            (9, 9, &[("one", "0 миллиард"), ("few", "0 миллиарда"), ("many", "0 миллиардов"), ("other", "0 миллиарда")]),
+            (12, 12, &[("one", "0 триллион"), ("few", "0 триллиона"), ("many", "0 триллионов"), ("other", "0 триллиона")]),
+        ],
+    ),
+    (
+        "zh",
+        &[(4, 4, &[("other", "0万")]), (8, 8, &[("other", "0亿")]), (12, 12, &[("other", "0万亿")])],
+        &[(4, 4, &[("other", "0万")]), (8, 8, &[("other", "0亿")]), (12, 12, &[("other", "0万亿")])],
+    ),
+];
+
+/// The ranges for a locale's language, short or long
+pub(crate) fn ranges(locale: &str, long: bool) -> &'static [Range] {
+    let tag = canonicalize_locale(locale);
+    let language = tag.split('-').next().unwrap_or_default();
+    let (_, short, long_ranges) = PATTERNS
+        .iter()
+        .find(|(candidate, ..)| *candidate == language)
+        .unwrap_or(&PATTERNS[0]);
+    if long {
+        long_ranges
+    } else {
+        short
+    }
+}
+
+/// The range a magnitude falls in, or None below the first
+pub(crate) fn range(ranges: &'static [Range], magnitude: u32) -> Option<&'static Range> {
+    ranges.iter().rev().find(|(from, ..)| *from <= magnitude)
+}
+
+/// Write a formatted number into a range's form for a plural category
+pub(crate) fn apply(range: &Range, category: &str, number: &str) -> String {
+    let (_, _, forms) = range;
+    let form = forms
+        .iter()
+        .find(|(candidate, _)| *candidate == category)
+        .or_else(|| forms.iter().find(|(candidate, _)| *candidate == "other"))
+        .map_or("0", |(_, form)| form);
+    form.replacen('0', number, 1)
+}
@@ -0,0 +1,225 @@
+// Compiled catalogs - a binary encoding of flattened catalog entries
+//
+// Layout (integers little-endian, strings as a u32 byte length then UTF-8):
+//   magic "I18C", version byte
+//   strings:  u32 count, then (key, value) pairs
+//   plurals:  u32 count, then per key: key, a byte with bit N set when the
+//             Nth of zero/one/two/few/many is present, those forms, other,
+//             u32 count of "=N" forms, then (value as in "1.5", form) pairs
+//   arrays:   u32 count, then per key: key, u32 item count, items
+//   metadata: u32 count, then per key: key, a byte with bits for
+//             description, comment and maxLength, then those present
+//             (maxLength as u64)
+// Entries are written in key order, so the same catalog always compiles to
+// the same bytes.
+//
+// Instance snapshots ("I18S") hold a JSON header string, a u32 catalog count
+// and per catalog its namespace, locale and entries laid out as above.
+use std::collections::HashMap;
+
+use super::{Catalog, ExactValue, KeyMetadata, PluralForms};
+
+const MAGIC: &[u8; 4] = b"I18C";
+const STATE_MAGIC: &[u8; 4] = b"I18S";
+const VERSION: u8 = 1;
+
+pub(crate) fn encode(catalog: &Catalog) -> Vec<u8> {
+    let mut out = Writer(Vec::new());
+    out.0.extend_from_slice(MAGIC);
+    out.0.push(VERSION);
+    write_catalog(&mut out, catalog);
+    out.0
+}
+
+pub(crate) fn decode(bytes: &[u8]) -> Result<Catalog, String> {
+    let mut input = Reader::open(bytes, MAGIC, "not a compiled catalog")?;
+    let catalog = read_catalog(&mut input)?;
+    input.finish()?;
+    Ok(catalog)
+}
+
+/// A snapshot: header JSON, then (namespace, locale, catalog) in order
+pub(crate) fn encode_state(header: &str, catalogs: &[(&str, &str, &Catalog)]) -> Vec<u8> {
+    let mut out = Writer(Vec::new());
+    out.0.extend_from_slice(STATE_MAGIC);
+    out.0.push(VERSION);
+    out.string(header);
+    out.u32(catalogs.len());
+    for (namespace, locale, catalog) in catalogs {
+        out.string(namespace);
+        out.string(locale);
+        write_catalog(&mut out, catalog);
+    }
+    out.0
+}
+
+pub(crate) type State = (String, Vec<(String, String, Catalog)>);
+
+pub(crate) fn decode_state(bytes: &[u8]) -> Result<State, String> {
+    let mut input = Reader::open(bytes, STATE_MAGIC, "not an instance snapshot")?;
+    let header = input.string()?.to_string();
+    let mut catalogs = Vec::new();
+    for _ in 0..input.u32()? {
+        let namespace = input.string()?.to_string();
+        let locale = input.string()?.to_string();
+        catalogs.push((namespace, locale, read_catalog(&mut input)?));
+    }
+    input.finish()?;
+    Ok((header, catalogs))
+}
+
+fn write_catalog(out: &mut Writer, catalog: &Catalog) {
+    out.entries(&catalog.strings, |out, text| out.string(text));
+    out.entries(&catalog.plurals, |out, forms| {
+        let optional = [&forms.zero, &forms.one, &forms.two, &forms.few, &forms.many];
+        out.0.push(mask(optional.iter().map(|form| form.is_some())));
+        for form in optional.into_iter().flatten() {
+            out.string(form);
+        }
+        out.string(&forms.other);
+        out.u32(forms.exact.len());
+        for (value, form) in &forms.exact {
+            out.string(&value.to_string());
+            out.string(form);
+        }
+    });
+    out.entries(&catalog.arrays, |out, items| {
+        out.u32(items.len());
+        for item in items {
+            out.string(item);
+        }
+    });
+    out.entries(&catalog.metadata, |out, metadata| {
+        let fields = [metadata.description.is_some(), metadata.comment.is_some(), metadata.max_length.is_some()];
+        out.0.push(mask(fields));
+        for text in [&metadata.description, &metadata.comment].into_iter().flatten() {
+            out.string(text);
+        }
+        if let Some(max_length) = metadata.max_length {
+            out.0.extend_from_slice(&max_length.to_le_bytes());
+        }
+    });
+}
+
+fn read_catalog(input: &mut Reader) -> Result<Catalog, String> {
+    let mut catalog = Catalog::default();
+
+    for _ in 0..input.u32()? {
+        let key = input.string()?;
+        catalog.strings.insert(key.to_string(), input.string()?.into());
+    }
+    for _ in 0..input.u32()? {
+        let key = input.string()?;
+        let mask = input.byte()?;
+        let mut optional = [None, None, None, None, None];
+        for (bit, form) in optional.iter_mut().enumerate() {
+            if mask & 1 << bit != 0 {
+                *form = Some(input.string()?.to_string());
+            }
+        }
+        let [zero, one, two, few, many] = optional;
+        let other = input.string()?.to_string();
+        let mut exact = Vec::new();
+        for _ in 0..input.u32()? {
+            let value = input.string()?;
+            let value = ExactValue::parse(value).ok_or_else(|| format!("invalid plural value {:?}", value))?;
+            exact.push((value, input.string()?.to_string()));
+        }
+        catalog.plurals.insert(key.to_string(), PluralForms { zero, one, two, few, many, other, exact });
+    }
+    for _ in 0..input.u32()? {
+        let key = input.string()?;
+        let items = (0..input.u32()?).map(|_| input.string().map(String::from)).collect::<Result<_, _>>()?;
+        catalog.arrays.insert(key.to_string(), items);
+    }
+    for _ in 0..input.u32()? {
+        let key = input.string()?;
+        let fields = input.byte()?;
+        let mut metadata = KeyMetadata::default();
+        if fields & 1 != 0 {
+            metadata.description = Some(input.string()?.to_string());
+        }
+        if fields & 2 != 0 {
+            metadata.comment = Some(input.string()?.to_string());
+        }
+        if fields & 4 != 0 {
+            metadata.max_length = Some(u64::from_le_bytes(input.take(8)?.try_into().unwrap()));
+        }
+        catalog.metadata.insert(key.to_string(), metadata);
+    }
+    Ok(catalog)
+}
+
+/// A byte with bit N set for each Nth flag that is set
+fn mask(flags: impl IntoIterator<Item = bool>) -> u8 {
+    flags.into_iter().enumerate().filter(|(_, set)| *set).fold(0, |mask, (bit, _)| mask | 1 << bit)
+}
+
+struct Writer(Vec<u8>);
+
+impl Writer {
+    fn u32(&mut self, value: usize) {
+        self.0.extend_from_slice(&(value as u32).to_le_bytes());
+    }
+
+    fn string(&mut self, text: &str) {
+        self.u32(text.len());
+        self.0.extend_from_slice(text.as_bytes());
+    }
+
+    fn entries<V>(&mut self, map: &HashMap<String, V>, mut write: impl FnMut(&mut Self, &V)) {
+        let mut entries: Vec<(&String, &V)> = map.iter().collect();
+        entries.sort_unstable_by_key(|(key, _)| *key);
+        self.u32(entries.len());
+        for (key, value) in entries {
+            self.string(key);
+            write(self, value);
+        }
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn open(bytes: &'a [u8], magic: &[u8; 4], not_magic: &str) -> Result<Reader<'a>, String> {
+        if bytes.get(..4) != Some(magic) {
+            return Err(not_magic.to_string());
+        }
+        if bytes.get(4) != Some(&VERSION) {
+            return Err(format!("unsupported version {}", bytes.get(4).copied().unwrap_or(0)));
+        }
+        Ok(Reader { bytes, offset: 5 })
+    }
+
+    fn finish(&self) -> Result<(), String> {
+        match self.offset == self.bytes.len() {
+            true => Ok(()),
+            false => Err(format!("trailing data at byte {}", self.offset)),
+        }
+    }
+
+    fn take(&mut self, length: usize) -> Result<&'a [u8], String> {
+        let end = self.offset.checked_add(length).filter(|end| *end <= self.bytes.len());
+        let end = end.ok_or_else(|| format!("truncated at byte {}", self.offset))?;
+        let slice = &self.bytes[self.offset..end];
+        self.offset = end;
+        Ok(slice)
+    }
+
+    fn byte(&mut self) -> Result<u8, String> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u32(&mut self) -> Result<usize, String> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()) as usize)
+    }
+
+    fn string(&mut self) -> Result<&'a str, String> {
+        let offset = self.offset;
+        let length = self.u32()?;
+        std::str::from_utf8(self.take(length)?).map_err(|_| format!("string at byte {} is not UTF-8", offset))
+    }
+}
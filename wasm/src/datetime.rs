@@ -0,0 +1,760 @@
+// Gregorian calendar data and CLDR pattern formatting for DateTimeFormat
+//
+// Names and patterns follow CLDR's gregorian calendar for the languages
+// below; any other language uses English. A pattern is CLDR's: runs of a
+// letter are fields ("EEEE" the weekday's wide name, "MM" the month with
+// two digits), text in single quotes is literal ("''" is a quote) and
+// anything else is written as it is. Numeric fields use the locale's
+// numbering system. Times are UTC shifted by a fixed offset; there is no
+// time zone database, so zones are written as offsets ("GMT+1").
+
+use super::numbers;
+
+/// Names in the three CLDR widths
+pub(crate) struct Names<const N: usize> {
+    pub wide: [&'static str; N],
+    pub abbreviated: [&'static str; N],
+    pub narrow: [&'static str; N],
+}
+
+/// A language's gregorian calendar data; patterns are full, long, medium
+/// and short
+pub(crate) struct Calendar {
+    pub language: &'static str,
+    /// Months as they appear in a date ("d MMMM")
+    pub months: Names<12>,
+    /// Months on their own ("LLLL"), where the language inflects them
+    pub standalone_months: Option<Names<12>>,
+    /// Sunday first
+    pub weekdays: Names<7>,
+    /// Before and after the epoch of the era
+    pub eras: Names<2>,
+    /// AM and PM
+    pub day_periods: Names<2>,
+    pub quarters: Names<4>,
+    pub date_patterns: [&'static str; 4],
+    pub time_patterns: [&'static str; 4],
+    /// How a date ({1}) and a time ({0}) combine, by the date's style
+    pub date_time_patterns: [&'static str; 4],
+    /// A zone offset ("GMT{0}"); the zero offset is written without one
+    pub gmt_format: &'static str,
+    /// The hour a "j" skeleton asks for: 'h' (1 to 12 with AM and PM) or
+    /// 'H' (0 to 23)
+    pub hour_cycle: char,
+    /// CLDR's availableFormats: (skeleton, pattern)
+    pub available_formats: &'static [(&'static str, &'static str)],
+}
+
+const ENGLISH: Calendar = Calendar {
+    language: "en",
+    months: Names {
+        wide: [
+            "January", "February", "March", "April", "May", "June", "July", "August", "September", "October", "November",
+            "December",
+        ],
+        abbreviated: ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"],
+        narrow: ["J", "F", "M", "A", "M", "J", "J", "A", "S", "O", "N", "D"],
+    },
+    standalone_months: None,
+    weekdays: Names {
+        wide: ["Sunday", "Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday"],
+        abbreviated: ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"],
+        narrow: ["S", "M", "T", "W", "T", "F", "S"],
+    },
+    eras: Names {
+        wide: ["Before Christ", "Anno Domini"],
+        abbreviated: ["BC", "AD"],
+        narrow: ["B", "A"],
+    },
+    day_periods: Names {
+        wide: ["AM", "PM"],
+        abbreviated: ["AM", "PM"],
+        narrow: ["a", "p"],
+    },
+    quarters: Names {
+        wide: ["1st quarter", "2nd quarter", "3rd quarter", "4th quarter"],
+        abbreviated: ["Q1", "Q2", "Q3", "Q4"],
+        narrow: ["1", "2", "3", "4"],
+    },
+    date_patterns: ["EEEE, MMMM d, y", "MMMM d, y", "MMM d, y", "M/d/yy"],
+    time_patterns: ["h:mm:ss\u{202f}a zzzz", "h:mm:ss\u{202f}a z", "h:mm:ss\u{202f}a", "h:mm\u{202f}a"],
+    date_time_patterns: ["{1} 'at' {0}", "{1} 'at' {0}", "{1}, {0}", "{1}, {0}"],
+    gmt_format: "GMT{0}",
+    hour_cycle: 'h',
+    available_formats: &[
+        ("d", "d"), ("E", "ccc"), ("Ed", "d E"), ("Gy", "y G"), ("GyMMMd", "MMM d, y G"), ("h", "h\u{202f}a"),
+        ("H", "HH"), ("hm", "h:mm\u{202f}a"), ("Hm", "HH:mm"), ("hms", "h:mm:ss\u{202f}a"), ("Hms", "HH:mm:ss"),
+        ("M", "L"), ("Md", "M/d"), ("MEd", "E, M/d"), ("MMM", "LLL"), ("MMMd", "MMM d"), ("MMMEd", "E, MMM d"),
+        ("MMMMd", "MMMM d"), ("ms", "mm:ss"), ("y", "y"), ("yM", "M/y"), ("yMd", "M/d/y"), ("yMEd", "E, M/d/y"),
+        ("yMMM", "MMM y"), ("yMMMd", "MMM d, y"), ("yMMMEd", "E, MMM d, y"), ("yMMMM", "MMMM y"),
+        ("yQQQ", "QQQ y"), ("yQQQQ", "QQQQ y"),
+    ],
+};
+
+const CALENDARS: &[Calendar] = &[
+    ENGLISH,
+    Calendar {
+        language: "de",
+        months: Names {
+            wide: [
+                "Januar", "Februar", "März", "April", "Mai", "Juni", "Juli", "August", "September", "Oktober", "November",
+                "Dezember",
+            ],
+            abbreviated: ["Jan.", "Feb.", "März", "Apr.", "Mai", "Juni", "Juli", "Aug.", "Sept.", "Okt.", "Nov.", "Dez."],
+            narrow: ["J", "F", "M", "A", "M", "J", "J", "A", "S", "O", "N", "D"],
+        },
+        standalone_months: None,
+        weekdays: Names {
+            wide: ["Sonntag", "Montag", "Dienstag", "Mittwoch", "Donnerstag", "Freitag", "Samstag"],
+            abbreviated: ["So.", "Mo.", "Di.", "Mi.", "Do.", "Fr.", "Sa."],
+            narrow: ["S", "M", "D", "M", "D", "F", "S"],
+        },
+        eras: Names {
+            wide: ["v. Chr.", "n. Chr."],
+            abbreviated: ["v. Chr.", "n. Chr."],
+            narrow: ["v. Chr.", "n. Chr."],
+        },
+        day_periods: Names {
+            wide: ["AM", "PM"],
+            abbreviated: ["AM", "PM"],
+            narrow: ["AM", "PM"],
+        },
+        quarters: Names {
+            wide: ["1. Quartal", "2. Quartal", "3. Quartal", "4. Quartal"],
+            abbreviated: ["Q1", "Q2", "Q3", "Q4"],
+            narrow: ["1", "2", "3", "4"],
+        },
+        date_patterns: ["EEEE, d. MMMM y", "d. MMMM y", "dd.MM.y", "dd.MM.yy"],
+        time_patterns: ["HH:mm:ss zzzz", "HH:mm:ss z", "HH:mm:ss", "HH:mm"],
+        date_time_patterns: ["{1} 'um' {0}", "{1} 'um' {0}", "{1}, {0}", "{1}, {0}"],
+        gmt_format: "GMT{0}",
+        hour_cycle: 'H',
+        available_formats: &[
+            ("d", "d"), ("E", "ccc"), ("Ed", "E, d."), ("Gy", "y G"), ("GyMMMd", "d. MMM y G"), ("h", "h 'Uhr' a"),
+            ("H", "HH 'Uhr'"), ("hm", "h:mm a"), ("Hm", "HH:mm"), ("hms", "h:mm:ss a"), ("Hms", "HH:mm:ss"), ("M", "L"),
+            ("Md", "d.M."), ("MEd", "E, d.M."), ("MMM", "LLL"), ("MMMd", "d. MMM"), ("MMMEd", "E, d. MMM"),
+            ("MMMMd", "d. MMMM"), ("ms", "mm:ss"), ("y", "y"), ("yM", "M/y"), ("yMd", "d.M.y"), ("yMEd", "E, d.M.y"),
+            ("yMMM", "MMM y"), ("yMMMd", "d. MMM y"), ("yMMMEd", "E, d. MMM y"), ("yMMMM", "MMMM y"),
+            ("yQQQ", "QQQ y"), ("yQQQQ", "QQQQ y"),
+        ],
+    },
+    Calendar {
+        language: "es",
+        months: Names {
+            wide: [
+                "enero", "febrero", "marzo", "abril", "mayo", "junio", "julio", "agosto", "septiembre", "octubre",
+                "noviembre", "diciembre",
+            ],
+            abbreviated: ["ene", "feb", "mar", "abr", "may", "jun", "jul", "ago", "sept", "oct", "nov", "dic"],
+            narrow: ["E", "F", "M", "A", "M", "J", "J", "A", "S", "O", "N", "D"],
+        },
+        standalone_months: None,
+        weekdays: Names {
+            wide: ["domingo", "lunes", "martes", "miércoles", "jueves", "viernes", "sábado"],
+            abbreviated: ["dom", "lun", "mar", "mié", "jue", "vie", "sáb"],
+            narrow: ["D", "L", "M", "X", "J", "V", "S"],
+        },
+        eras: Names {
+            wide: ["antes de Cristo", "después de Cristo"],
+            abbreviated: ["a. C.", "d. C."],
+            narrow: ["a. C.", "d. C."],
+        },
+        day_periods: Names {
+            wide: ["a. m.", "p. m."],
+            abbreviated: ["a. m.", "p. m."],
+            narrow: ["a. m.", "p. m."],
+        },
+        quarters: Names {
+            wide: ["1.er trimestre", "2.º trimestre", "3.er trimestre", "4.º trimestre"],
+            abbreviated: ["T1", "T2", "T3", "T4"],
+            narrow: ["1", "2", "3", "4"],
+        },
+        date_patterns: ["EEEE, d 'de' MMMM 'de' y", "d 'de' MMMM 'de' y", "d MMM y", "d/M/yy"],
+        time_patterns: ["H:mm:ss (zzzz)", "H:mm:ss z", "H:mm:ss", "H:mm"],
+        date_time_patterns: ["{1}, {0}", "{1}, {0}", "{1}, {0}", "{1}, {0}"],
+        gmt_format: "GMT{0}",
+        hour_cycle: 'H',
+        available_formats: &[
+            ("d", "d"), ("E", "ccc"), ("Ed", "E d"), ("Gy", "y G"), ("GyMMMd", "d MMM y G"), ("h", "h a"), ("H", "H"),
+            ("hm", "h:mm a"), ("Hm", "H:mm"), ("hms", "h:mm:ss a"), ("Hms", "H:mm:ss"), ("M", "L"), ("Md", "d/M"),
+            ("MEd", "E, d/M"), ("MMM", "LLL"), ("MMMd", "d MMM"), ("MMMEd", "E, d MMM"), ("MMMMd", "d 'de' MMMM"),
+            ("ms", "mm:ss"), ("y", "y"), ("yM", "M/y"), ("yMd", "d/M/y"), ("yMEd", "EEE, d/M/y"), ("yMMM", "MMM y"),
+            ("yMMMd", "d MMM y"), ("yMMMEd", "EEE, d MMM y"), ("yMMMM", "MMMM 'de' y"),
+            ("yMMMMd", "d 'de' MMMM 'de' y"), ("yMMMMEEEEd", "EEEE, d 'de' MMMM 'de' y"),
+            ("yQQQ", "QQQ y"), ("yQQQQ", "QQQQ 'de' y"),
+        ],
+    },
+    Calendar {
+        language: "fr",
+        months: Names {
+            wide: [
+                "janvier", "février", "mars", "avril", "mai", "juin", "juillet", "août", "septembre", "octobre",
+                "novembre", "décembre",
+            ],
+            abbreviated: [
+                "janv.", "févr.", "mars", "avr.", "mai", "juin", "juil.", "août", "sept.", "oct.", "nov.", "déc.",
+            ],
+            narrow: ["J", "F", "M", "A", "M", "J", "J", "A", "S", "O", "N", "D"],
+        },
+        standalone_months: None,
+        weekdays: Names {
+            wide: ["dimanche", "lundi", "mardi", "mercredi", "jeudi", "vendredi", "samedi"],
+            abbreviated: ["dim.", "lun.", "mar.", "mer.", "jeu.", "ven.", "sam."],
+            narrow: ["D", "L", "M", "M", "J", "V", "S"],
+        },
+        eras: Names {
+            wide: ["avant Jésus-Christ", "après Jésus-Christ"],
+            abbreviated: ["av. J.-C.", "ap. J.-C."],
+            narrow: ["av. J.-C.", "ap. J.-C."],
+        },
+        day_periods: Names {
+            wide: ["AM", "PM"],
+            abbreviated: ["AM", "PM"],
+            narrow: ["AM", "PM"],
+        },
+        quarters: Names {
+            wide: ["1er trimestre", "2e trimestre", "3e trimestre", "4e trimestre"],
+            abbreviated: ["T1", "T2", "T3", "T4"],
+            narrow: ["1", "2", "3", "4"],
+        },
+        date_patterns: ["EEEE d MMMM y", "d MMMM y", "d MMM y", "dd/MM/y"],
+        time_patterns: ["HH:mm:ss zzzz", "HH:mm:ss z", "HH:mm:ss", "HH:mm"],
+        date_time_patterns: ["{1} 'à' {0}", "{1} 'à' {0}", "{1} {0}", "{1} {0}"],
+        gmt_format: "UTC{0}",
+        hour_cycle: 'H',
+        available_formats: &[
+            ("d", "d"), ("E", "E"), ("Ed", "E d"), ("Gy", "y G"), ("GyMMMd", "d MMM y G"), ("h", "h a"),
+            ("H", "HH 'h'"), ("hm", "h:mm a"), ("Hm", "HH:mm"), ("hms", "h:mm:ss a"), ("Hms", "HH:mm:ss"), ("M", "L"),
+            ("Md", "dd/MM"), ("MEd", "E dd/MM"), ("MMM", "LLL"), ("MMMd", "d MMM"), ("MMMEd", "E d MMM"),
+            ("MMMMd", "d MMMM"), ("ms", "mm:ss"), ("y", "y"), ("yM", "MM/y"), ("yMd", "dd/MM/y"), ("yMEd", "E dd/MM/y"),
+            ("yMMM", "MMM y"), ("yMMMd", "d MMM y"), ("yMMMEd", "E d MMM y"), ("yMMMM", "MMMM y"),
+            ("yQQQ", "QQQ y"), ("yQQQQ", "QQQQ y"),
+        ],
+    },
+    Calendar {
+        language: "ja",
+        months: Names {
+            wide: ["1月", "2月", "3月", "4月", "5月", "6月", "7月", "8月", "9月", "10月", "11月", "12月"],
+            abbreviated: ["1月", "2月", "3月", "4月", "5月", "6月", "7月", "8月", "9月", "10月", "11月", "12月"],
+            narrow: ["1", "2", "3", "4", "5", "6", "7", "8", "9", "10", "11", "12"],
+        },
+        standalone_months: None,
+        weekdays: Names {
+            wide: ["日曜日", "月曜日", "火曜日", "水曜日", "木曜日", "金曜日", "土曜日"],
+            abbreviated: ["日", "月", "火", "水", "木", "金", "土"],
+            narrow: ["日", "月", "火", "水", "木", "金", "土"],
+        },
+        eras: Names {
+            wide: ["紀元前", "西暦"],
+            abbreviated: ["紀元前", "西暦"],
+            narrow: ["BC", "AD"],
+        },
+        day_periods: Names {
+            wide: ["午前", "午後"],
+            abbreviated: ["午前", "午後"],
+            narrow: ["午前", "午後"],
+        },
+        quarters: Names {
+            wide: ["第1四半期", "第2四半期", "第3四半期", "第4四半期"],
+            abbreviated: ["Q1", "Q2", "Q3", "Q4"],
+            narrow: ["1", "2", "3", "4"],
+        },
+        date_patterns: ["y年M月d日EEEE", "y年M月d日", "y/MM/dd", "y/MM/dd"],
+        time_patterns: ["H時mm分ss秒 zzzz", "H:mm:ss z", "H:mm:ss", "H:mm"],
+        date_time_patterns: ["{1} {0}", "{1} {0}", "{1} {0}", "{1} {0}"],
+        gmt_format: "GMT{0}",
+        hour_cycle: 'H',
+        available_formats: &[
+            ("d", "d日"), ("E", "ccc"), ("Ed", "d日(E)"), ("Gy", "Gy年"), ("GyMMMd", "Gy年M月d日"), ("h", "aK時"), ("H", "H時"),
+            ("hm", "aK:mm"), ("Hm", "H:mm"), ("hms", "aK:mm:ss"), ("Hms", "H:mm:ss"), ("M", "M月"), ("Md", "M/d"),
+            ("MEd", "M/d(E)"), ("MMM", "M月"), ("MMMd", "M月d日"), ("MMMEd", "M月d日(E)"), ("MMMMd", "M月d日"),
+            ("ms", "mm:ss"), ("y", "y年"), ("yM", "y/M"), ("yMd", "y/M/d"), ("yMEd", "y/M/d(E)"), ("yMMM", "y年M月"),
+            ("yMMMd", "y年M月d日"), ("yMMMEd", "y年M月d日(E)"), ("yMMMM", "y年M月"),
+            ("yQQQ", "y/QQQ"), ("yQQQQ", "y年QQQQ"),
+        ],
+    },
+    Calendar {
+        language: "ru",
+        months: Names {
+            wide: [
+                "января", "февраля", "марта", "апреля", "мая", "июня", "июля", "августа", "сентября", "октября",
+                "ноября", "декабря",
+            ],
+            abbreviated: [
+                "янв.", "февр.", "мар.", "апр.", "мая", "июн.", "июл.", "авг.", "сент.", "окт.", "нояб.", "дек.",
+            ],
+            narrow: ["Я", "Ф", "М", "А", "М", "И", "И", "А", "С", "О", "Н", "Д"],
+        },
+        standalone_months: Some(Names {
+            wide: [
+                "январь", "февраль", "март", "апрель", "май", "июнь", "июль", "август", "сентябрь", "октябрь",
+                "ноябрь", "декабрь",
+            ],
+            abbreviated: [
+                "янв.", "февр.", "март", "апр.", "май", "июнь", "июль", "авг.", "сент.", "окт.", "нояб.", "дек.",
+            ],
+            narrow: ["Я", "Ф", "М", "А", "М", "И", "И", "А", "С", "О", "Н", "Д"],
+        }),
+        weekdays: Names {
+            wide: ["воскресенье", "понедельник", "вторник", "среда", "четверг", "пятница", "суббота"],
+            abbreviated: ["вс", "пн", "вт", "ср", "чт", "пт", "сб"],
+            narrow: ["В", "П", "В", "С", "Ч", "П", "С"],
+        },
+        eras: Names {
+            wide: ["до Рождества Христова", "от Рождества Христова"],
+            abbreviated: ["до н. э.", "н. э."],
+            narrow: ["до н.э.", "н.э."],
+        },
+        day_periods: Names {
+            wide: ["AM", "PM"],
+            abbreviated: ["AM", "PM"],
+            narrow: ["AM", "PM"],
+        },
+        quarters: Names {
+            wide: ["1-й квартал", "2-й квартал", "3-й квартал", "4-й квартал"],
+            abbreviated: ["1-й кв.", "2-й кв.", "3-й кв.", "4-й кв."],
+            narrow: ["1", "2", "3", "4"],
+        },
+        date_patterns: ["EEEE, d MMMM y 'г'.", "d MMMM y 'г'.", "d MMM y 'г'.", "dd.MM.y"],
+        time_patterns: ["HH:mm:ss zzzz", "HH:mm:ss z", "HH:mm:ss", "HH:mm"],
+        date_time_patterns: ["{1}, {0}", "{1}, {0}", "{1}, {0}", "{1}, {0}"],
+        gmt_format: "GMT{0}",
+        hour_cycle: 'H',
+        available_formats: &[
+            ("d", "d"), ("E", "ccc"), ("Ed", "ccc, d"), ("Gy", "y 'г'. G"), ("GyMMMd", "d MMM y 'г'. G"), ("h", "h a"),
+            ("H", "HH"), ("hm", "h:mm a"), ("Hm", "HH:mm"), ("hms", "h:mm:ss a"), ("Hms", "HH:mm:ss"), ("M", "L"),
+            ("Md", "dd.MM"), ("MEd", "E, dd.MM"), ("MMM", "LLL"), ("MMMd", "d MMM"), ("MMMEd", "ccc, d MMM"),
+            ("MMMMd", "d MMMM"), ("ms", "mm:ss"), ("y", "y"), ("yM", "MM.y"), ("yMd", "dd.MM.y"),
+            ("yMEd", "ccc, dd.MM.y 'г'."), ("yMMM", "LLL y 'г'."), ("yMMMd", "d MMM y 'г'."),
+            ("yMMMEd", "E, d MMM y 'г'."), ("yMMMM", "LLLL y 'г'."),
+            ("yQQQ", "QQQ y 'г'."), ("yQQQQ", "QQQQ y 'г'."),
+        ],
+    },
+    Calendar {
+        language: "zh",
+        months: Names {
+            wide: [
+                "一月", "二月", "三月", "四月", "五月", "六月", "七月", "八月", "九月", "十月", "十一月", "十二月",
+            ],
+            abbreviated: ["1月", "2月", "3月", "4月", "5月", "6月", "7月", "8月", "9月", "10月", "11月", "12月"],
+            narrow: ["1", "2", "3", "4", "5", "6", "7", "8", "9", "10", "11", "12"],
+        },
+        standalone_months: None,
+        weekdays: Names {
+            wide: ["星期日", "星期一", "星期二", "星期三", "星期四", "星期五", "星期六"],
+            abbreviated: ["周日", "周一", "周二", "周三", "周四", "周五", "周六"],
+            narrow: ["日", "一", "二", "三", "四", "五", "六"],
+        },
+        eras: Names {
+            wide: ["公元前", "公元"],
+            abbreviated: ["公元前", "公元"],
+            narrow: ["公元前", "公元"],
+        },
+        day_periods: Names {
+            wide: ["上午", "下午"],
+            abbreviated: ["上午", "下午"],
+            narrow: ["上午", "下午"],
+        },
+        quarters: Names {
+            wide: ["第一季度", "第二季度", "第三季度", "第四季度"],
+            abbreviated: ["1季度", "2季度", "3季度", "4季度"],
+            narrow: ["1", "2", "3", "4"],
+        },
+        date_patterns: ["y年M月d日EEEE", "y年M月d日", "y年M月d日", "y/M/d"],
+        time_patterns: ["zzzz HH:mm:ss", "z HH:mm:ss", "HH:mm:ss", "HH:mm"],
+        date_time_patterns: ["{1} {0}", "{1} {0}", "{1} {0}", "{1} {0}"],
+        gmt_format: "GMT{0}",
+        hour_cycle: 'H',
+        available_formats: &[
+            ("d", "d日"), ("E", "ccc"), ("Ed", "d日E"), ("Gy", "Gy年"), ("GyMMMd", "Gy年M月d日"), ("h", "ah时"), ("H", "H时"),
+            ("hm", "ah:mm"), ("Hm", "HH:mm"), ("hms", "ah:mm:ss"), ("Hms", "HH:mm:ss"), ("M", "M月"), ("Md", "M/d"),
+            ("MEd", "M/dE"), ("MMM", "LLL"), ("MMMd", "M月d日"), ("MMMEd", "M月d日E"), ("MMMMd", "M月d日"), ("ms", "mm:ss"),
+            ("y", "y年"), ("yM", "y/M"), ("yMd", "y/M/d"), ("yMEd", "y/M/dE"), ("yMMM", "y年M月"), ("yMMMd", "y年M月d日"),
+            ("yMMMEd", "y年M月d日E"), ("yMMMM", "y年M月"),
+            ("yQQQ", "y年第Q季度"), ("yQQQQ", "y年第Q季度"),
+        ],
+    },
+];
+
+/// The calendar data for a locale's language
+pub(crate) fn calendar(locale: &str) -> &'static Calendar {
+    let tag = super::canonicalize_locale(locale);
+    let language = tag.split('-').next().unwrap_or_default();
+    CALENDARS.iter().find(|calendar| calendar.language == language).unwrap_or(&ENGLISH)
+}
+
+/// A skeleton field: (kind, letter, count). Letters asking for the same
+/// thing share a kind ('M' for M and L, 'h' for h and K, 'z' for zones),
+/// which availableFormats match on
+type SkeletonField = (char, char, usize);
+
+/// The fields of a skeleton ("yMMMd", "jm"), with j as the locale's hour;
+/// None for literals, unknown letters or a kind asked for twice. Day
+/// periods are left out: the hour brings them.
+fn skeleton_fields(skeleton: &str, hour_cycle: char) -> Option<Vec<SkeletonField>> {
+    let mut fields: Vec<SkeletonField> = Vec::new();
+    for token in tokens(skeleton) {
+        let Token::Field(letter, count) = token else {
+            return None;
+        };
+        let letter = if letter == 'j' { hour_cycle } else { letter };
+        if matches!(letter, 'a' | 'b' | 'B') {
+            continue;
+        }
+        let kind = kind(letter)?;
+        if fields.iter().any(|field| field.0 == kind || matches!((field.0, kind), ('h', 'H') | ('H', 'h'))) {
+            return None;
+        }
+        fields.push((kind, letter, count));
+    }
+    Some(fields)
+}
+
+/// The kind of a field letter
+fn kind(letter: char) -> Option<char> {
+    Some(match letter {
+        'G' => 'G',
+        'y' | 'Y' | 'u' => 'y',
+        'Q' | 'q' => 'Q',
+        'M' | 'L' => 'M',
+        'd' => 'd',
+        'E' | 'c' | 'e' => 'E',
+        'h' | 'K' => 'h',
+        'H' | 'k' => 'H',
+        'm' => 'm',
+        's' => 's',
+        'z' | 'Z' | 'O' | 'v' | 'V' | 'x' | 'X' => 'z',
+        _ => return None,
+    })
+}
+
+/// Whether a field of a kind and count is written as text
+fn is_text(kind: char, count: usize) -> bool {
+    match kind {
+        'Q' | 'M' => count >= 3,
+        'G' | 'E' => true,
+        _ => false,
+    }
+}
+
+/// The locale's preferred pattern for a skeleton, as ICU's
+/// DateTimePatternGenerator: the availableFormats entry with the same
+/// fields nearest in length (text and numbers never stand in for each
+/// other), its fields lengthened to the skeleton's. A skeleton with no
+/// entry is split into its date and its time, joined as the date's style
+/// would join them, and a zone goes after. "yMMMd" is "MMM d, y" in en and
+/// "d. MMM y" in de; None for a skeleton no entry can write.
+pub(crate) fn best_pattern(locale: &str, skeleton: &str) -> Option<String> {
+    let calendar = calendar(locale);
+    let mut fields = skeleton_fields(skeleton, calendar.hour_cycle).filter(|fields| !fields.is_empty())?;
+    let zone = fields.iter().position(|field| field.0 == 'z').map(|index| fields.remove(index));
+    let pattern = match nearest(calendar, &fields) {
+        Some(pattern) => pattern,
+        None => {
+            let (date, time): (Vec<_>, Vec<_>) = fields.iter().partition(|field| "GyQMdE".contains(field.0));
+            let (date, time) = (nearest(calendar, &date)?, nearest(calendar, &time)?);
+            let month = fields.iter().find(|field| field.0 == 'M').map_or(0, |field| field.2);
+            let style = match (month, fields.iter().any(|field| field.0 == 'E')) {
+                (4.., true) => 0,
+                (4.., false) => 1,
+                (3, _) => 2,
+                _ => 3,
+            };
+            calendar.date_time_patterns[style].replace("{1}", &date).replace("{0}", &time)
+        }
+    };
+    Some(match zone {
+        Some((_, letter, count)) if pattern.is_empty() => letter.to_string().repeat(count),
+        Some((_, letter, count)) => format!("{} {}", pattern, letter.to_string().repeat(count)),
+        None => pattern,
+    })
+}
+
+/// The availableFormats pattern nearest to fields, adjusted to them
+fn nearest(calendar: &Calendar, fields: &[SkeletonField]) -> Option<String> {
+    if fields.is_empty() {
+        return Some(String::new());
+    }
+    let (_, pattern) = calendar
+        .available_formats
+        .iter()
+        .filter_map(|(skeleton, pattern)| {
+            let candidate = skeleton_fields(skeleton, calendar.hour_cycle)?;
+            if candidate.len() != fields.len() {
+                return None;
+            }
+            let mut distance = 0;
+            for &(kind, _, count) in fields {
+                let &(_, _, length) = candidate.iter().find(|field| field.0 == kind)?;
+                distance += if is_text(kind, count) != is_text(kind, length) { 0x1000 } else { count.abs_diff(length) };
+            }
+            Some((distance, pattern))
+        })
+        .min_by_key(|(distance, _)| *distance)?;
+
+    let original = tokens(pattern);
+    let adjusted: Vec<Token> = original
+        .iter()
+        .cloned()
+        .map(|token| {
+            let Token::Field(letter, count) = token else {
+                return token;
+            };
+            let field = kind(letter).and_then(|kind| fields.iter().find(|field| field.0 == kind));
+            match field {
+                // y is the whole year however many letters ask for it, yy two digits
+                Some(&('y', _, wanted)) => Token::Field(letter, if wanted == 2 { 2 } else { count }),
+                // K and k replace h and H; h and H keep the locale's own letter
+                Some(&('h' | 'H', wanted @ ('K' | 'k'), wanted_count)) => Token::Field(wanted, count.max(wanted_count)),
+                // A number stays a number where the locale writes one for text (ja "M月")
+                Some(&(kind, _, wanted_count)) if is_text(kind, count) == is_text(kind, wanted_count) => {
+                    Token::Field(letter, count.max(wanted_count))
+                }
+                _ => Token::Field(letter, count),
+            }
+        })
+        .collect();
+    Some(if adjusted == original { pattern.to_string() } else { pattern_of(&adjusted) })
+}
+
+impl<const N: usize> Names<N> {
+    /// The names for a field's width: 1 to 3 letters abbreviated, 4 wide,
+    /// 5 narrow
+    pub fn width(&self, count: usize) -> &[&'static str; N] {
+        match count {
+            4 => &self.wide,
+            5 => &self.narrow,
+            _ => &self.abbreviated,
+        }
+    }
+}
+
+/// The fields of a moment in the proleptic Gregorian calendar
+pub(crate) struct Fields {
+    /// Astronomical year: 0 is 1 BC
+    pub year: i64,
+    /// 1 to 12
+    pub month: u32,
+    pub day: u32,
+    /// 1 to 366
+    pub day_of_year: u32,
+    /// 0 is Sunday
+    pub weekday: u32,
+    pub hour: u32,
+    pub minute: u32,
+    pub second: u32,
+    pub millisecond: u32,
+    /// Minutes east of UTC
+    pub offset: i32,
+}
+
+impl Fields {
+    /// The fields of epoch milliseconds shifted by an offset in minutes
+    pub fn from_epoch_millis(millis: f64, offset: i32) -> Fields {
+        let local = millis.floor() as i64 + i64::from(offset) * 60_000;
+        let days = local.div_euclid(86_400_000);
+        let time = local.rem_euclid(86_400_000);
+        let (year, month, day) = civil_from_days(days);
+        Fields {
+            year,
+            month,
+            day,
+            day_of_year: (days - days_from_civil(year, 1, 1)) as u32 + 1,
+            weekday: (days + 4).rem_euclid(7) as u32,
+            hour: (time / 3_600_000) as u32,
+            minute: (time / 60_000 % 60) as u32,
+            second: (time / 1000 % 60) as u32,
+            millisecond: (time % 1000) as u32,
+            offset,
+        }
+    }
+}
+
+/// Year, month and day of days since 1970-01-01 (Howard Hinnant's
+/// civil_from_days)
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month_index + 2) / 5 + 1) as u32;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 } as u32;
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+/// Days since 1970-01-01 of a date
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = year - i64::from(month <= 2);
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let month = i64::from(month);
+    let day_of_year = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + i64::from(day) - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Write fields in a CLDR pattern for a locale
+pub(crate) fn format(pattern: &str, fields: &Fields, locale: &str) -> String {
+    let calendar = calendar(locale);
+    let zero = numbers::symbols(locale).zero;
+    let localize = |digits: String| match zero {
+        '0' => digits,
+        zero => digits
+            .chars()
+            .map(|c| c.to_digit(10).and_then(|digit| char::from_u32(zero as u32 + digit)).unwrap_or(c))
+            .collect(),
+    };
+    let number = |value: i64, width: usize| localize(format!("{:0width$}", value, width = width));
+
+    let mut out = String::with_capacity(pattern.len() * 2);
+    for token in tokens(pattern) {
+        let (c, count) = match token {
+            Token::Literal(text) => {
+                out.push_str(&text);
+                continue;
+            }
+            Token::Field(c, count) => (c, count),
+        };
+        let year_of_era = if fields.year > 0 { fields.year } else { 1 - fields.year };
+        let hour12 = match fields.hour % 12 {
+            0 => 12,
+            hour => hour,
+        };
+        match c {
+            'G' => out.push_str(calendar.eras.width(count)[usize::from(fields.year > 0)]),
+            'y' | 'Y' if count == 2 => out.push_str(&number(year_of_era % 100, 2)),
+            'y' | 'Y' => out.push_str(&number(year_of_era, count)),
+            'u' => out.push_str(&number(fields.year, count)),
+            'M' | 'L' if count <= 2 => out.push_str(&number(fields.month.into(), count)),
+            'M' | 'L' => {
+                let names = match (c, &calendar.standalone_months) {
+                    ('L', Some(standalone)) => standalone,
+                    _ => &calendar.months,
+                };
+                out.push_str(names.width(count)[fields.month as usize - 1]);
+            }
+            'Q' | 'q' if count <= 2 => out.push_str(&number(fields.month.div_ceil(3).into(), count)),
+            'Q' | 'q' => out.push_str(calendar.quarters.width(count)[(fields.month as usize - 1) / 3]),
+            'd' => out.push_str(&number(fields.day.into(), count)),
+            'D' => out.push_str(&number(fields.day_of_year.into(), count)),
+            'E' | 'c' | 'e' => out.push_str(calendar.weekdays.width(count.max(3))[fields.weekday as usize]),
+            'a' | 'b' | 'B' => out.push_str(calendar.day_periods.width(count)[usize::from(fields.hour >= 12)]),
+            'h' => out.push_str(&number(hour12.into(), count)),
+            'H' => out.push_str(&number(fields.hour.into(), count)),
+            'K' => out.push_str(&number((fields.hour % 12).into(), count)),
+            'k' => out.push_str(&number(if fields.hour == 0 { 24 } else { fields.hour.into() }, count)),
+            'm' => out.push_str(&number(fields.minute.into(), count)),
+            's' => out.push_str(&number(fields.second.into(), count)),
+            'S' => {
+                let fraction = format!("{:03}", fields.millisecond);
+                out.push_str(&localize(fraction.chars().chain(std::iter::repeat('0')).take(count).collect()));
+            }
+            'z' | 'O' | 'v' | 'V' => out.push_str(&gmt(calendar, fields.offset, count >= 4)),
+            'Z' if count == 4 => out.push_str(&gmt(calendar, fields.offset, true)),
+            'Z' | 'x' | 'X' => out.push_str(&iso_offset(fields.offset, c, count)),
+            _ => (0..count).for_each(|_| out.push(c)),
+        }
+    }
+    out
+}
+
+/// A pattern's pieces: literal text, or a field letter and its count
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Token {
+    Literal(String),
+    Field(char, usize),
+}
+
+/// Split a CLDR pattern into literal text (unquoted) and fields
+pub(crate) fn tokens(pattern: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut literal = String::new();
+    let mut chars = pattern.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '\'' {
+            if chars.peek() == Some(&'\'') {
+                chars.next();
+                literal.push('\'');
+                continue;
+            }
+            while let Some(c) = chars.next() {
+                match c {
+                    '\'' if chars.peek() == Some(&'\'') => {
+                        chars.next();
+                        literal.push('\'');
+                    }
+                    '\'' => break,
+                    c => literal.push(c),
+                }
+            }
+            continue;
+        }
+        if !c.is_ascii_alphabetic() {
+            literal.push(c);
+            continue;
+        }
+        let mut count = 1;
+        while chars.peek() == Some(&c) {
+            chars.next();
+            count += 1;
+        }
+        if !literal.is_empty() {
+            tokens.push(Token::Literal(std::mem::take(&mut literal)));
+        }
+        tokens.push(Token::Field(c, count));
+    }
+    if !literal.is_empty() {
+        tokens.push(Token::Literal(literal));
+    }
+    tokens
+}
+
+/// Write tokens back as a pattern, quoting literal letters and quotes
+fn pattern_of(tokens: &[Token]) -> String {
+    let mut pattern = String::new();
+    for token in tokens {
+        match token {
+            Token::Field(c, count) => (0..*count).for_each(|_| pattern.push(*c)),
+            Token::Literal(text) if text.contains(|c: char| c.is_ascii_alphabetic() || c == '\'') => {
+                pattern.push('\'');
+                pattern.push_str(&text.replace('\'', "''"));
+                pattern.push('\'');
+            }
+            Token::Literal(text) => pattern.push_str(text),
+        }
+    }
+    pattern
+}
+
+/// A localized GMT offset: "GMT", "GMT+1", "GMT+5:30", or "GMT+01:00" long
+fn gmt(calendar: &Calendar, offset: i32, long: bool) -> String {
+    if offset == 0 {
+        return calendar.gmt_format.replace("{0}", "");
+    }
+    let sign = if offset < 0 { '-' } else { '+' };
+    let (hours, minutes) = (offset.abs() / 60, offset.abs() % 60);
+    let offset = match (long, minutes) {
+        (true, _) => format!("{}{:02}:{:02}", sign, hours, minutes),
+        (false, 0) => format!("{}{}", sign, hours),
+        (false, _) => format!("{}{}:{:02}", sign, hours, minutes),
+    };
+    calendar.gmt_format.replace("{0}", &offset)
+}
+
+/// An ISO 8601 offset: "+0100" (Z, x with 2, X with 2), "+01:00" (ZZZZZ,
+/// xxx, XXX), "+01" (x, X); X writes "Z" for UTC
+fn iso_offset(offset: i32, letter: char, count: usize) -> String {
+    if offset == 0 && (letter == 'X' || (letter == 'Z' && count == 5)) {
+        return "Z".to_string();
+    }
+    let sign = if offset < 0 { '-' } else { '+' };
+    let (hours, minutes) = (offset.abs() / 60, offset.abs() % 60);
+    match (letter, count) {
+        ('Z', 5) | ('x' | 'X', 3 | 5) => format!("{}{:02}:{:02}", sign, hours, minutes),
+        ('x' | 'X', 1) if minutes == 0 => format!("{}{:02}", sign, hours),
+        _ => format!("{}{:02}{:02}", sign, hours, minutes),
+    }
+}
@@ -0,0 +1,213 @@
+// Errors from the fallible APIs
+//
+// Each variant reaches JavaScript as an Error named after it (e.g.
+// "InvalidCatalogError") with a stable `code` ("INVALID_CATALOG") and the
+// variant's fields as properties, so callers can branch on `e.code` and read
+// `e.line` instead of parsing messages. The messages are unchanged.
+use std::fmt;
+
+use wasm_bindgen::JsValue;
+
+use super::ConfigWarning;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum I18nError {
+    /// A config that does not parse or has the wrong shape, or a strict one
+    /// that validation warned about
+    InvalidConfig { message: String, warnings: Vec<ConfigWarning> },
+    /// A catalog that does not parse. format is "json", "po", "mo", "arb",
+    /// "strings", "stringsdict", "android", "properties", "ts", "resx",
+    /// "tmx", "compiled", "state", "gzip" or "deflate"; line and column are
+    /// 1-based, where the parser knows them.
+    InvalidCatalog {
+        format: &'static str,
+        message: String,
+        line: Option<usize>,
+        column: Option<usize>,
+    },
+    /// An argument that is not valid JSON
+    SyntaxError {
+        argument: &'static str,
+        message: String,
+        line: usize,
+        column: usize,
+    },
+    /// A well-formed argument with a value that cannot be used
+    InvalidArgument { argument: &'static str, message: String },
+    /// Strict mode: no locale in the fallback chain has the key
+    MissingKey { key: String, locale: String },
+    /// missingVariables "error": a {{name}} placeholder without a value
+    MissingVariable { name: String },
+    /// A locale tag that is not usable
+    UnsupportedLocale { locale: String },
+    /// mergeCatalog with "error-on-conflict": the keys that differ
+    MergeConflict { keys: Vec<String> },
+    /// A result that could not be turned into JSON
+    Serialization { message: String },
+}
+
+impl I18nError {
+    /// The stable code set on the JS error
+    pub fn code(&self) -> &'static str {
+        match self {
+            I18nError::InvalidConfig { .. } => "INVALID_CONFIG",
+            I18nError::InvalidCatalog { .. } => "INVALID_CATALOG",
+            I18nError::SyntaxError { .. } => "SYNTAX_ERROR",
+            I18nError::InvalidArgument { .. } => "INVALID_ARGUMENT",
+            I18nError::MissingKey { .. } => "MISSING_KEY",
+            I18nError::MissingVariable { .. } => "MISSING_VARIABLE",
+            I18nError::UnsupportedLocale { .. } => "UNSUPPORTED_LOCALE",
+            I18nError::MergeConflict { .. } => "MERGE_CONFLICT",
+            I18nError::Serialization { .. } => "SERIALIZATION",
+        }
+    }
+
+    /// The JS error's name
+    pub fn name(&self) -> &'static str {
+        match self {
+            I18nError::InvalidConfig { .. } => "InvalidConfigError",
+            I18nError::InvalidCatalog { .. } => "InvalidCatalogError",
+            I18nError::SyntaxError { .. } => "SyntaxError",
+            I18nError::InvalidArgument { .. } => "InvalidArgumentError",
+            I18nError::MissingKey { .. } => "MissingKeyError",
+            I18nError::MissingVariable { .. } => "MissingVariableError",
+            I18nError::UnsupportedLocale { .. } => "UnsupportedLocaleError",
+            I18nError::MergeConflict { .. } => "MergeConflictError",
+            I18nError::Serialization { .. } => "SerializationError",
+        }
+    }
+
+    pub(crate) fn config(error: impl fmt::Display) -> I18nError {
+        I18nError::InvalidConfig { message: error.to_string(), warnings: Vec::new() }
+    }
+
+    pub(crate) fn invalid_config(warnings: Vec<ConfigWarning>) -> I18nError {
+        let messages: Vec<&str> = warnings.iter().map(|warning| warning.message.as_str()).collect();
+        I18nError::InvalidConfig { message: messages.join("; "), warnings }
+    }
+
+    /// A JSON catalog serde_json rejected, with its position
+    pub(crate) fn json_catalog(error: serde_json::Error) -> I18nError {
+        I18nError::InvalidCatalog {
+            format: "json",
+            message: error.to_string(),
+            line: Some(error.line()),
+            column: Some(error.column()),
+        }
+    }
+
+    /// A catalog parser's error; the "line N: ..." prefix the line-based
+    /// parsers use becomes the line
+    pub(crate) fn catalog(format: &'static str, error: impl fmt::Display) -> I18nError {
+        let message = error.to_string();
+        let line = message
+            .strip_prefix("line ")
+            .and_then(|rest| rest.split_once(':'))
+            .and_then(|(line, _)| line.parse().ok());
+        I18nError::InvalidCatalog { format, message, line, column: None }
+    }
+
+    /// A JSON argument serde_json rejected: malformed JSON is a SyntaxError,
+    /// JSON of the wrong shape an InvalidArgument
+    pub(crate) fn json(argument: &'static str, error: serde_json::Error) -> I18nError {
+        match error.classify() {
+            serde_json::error::Category::Syntax | serde_json::error::Category::Eof => I18nError::SyntaxError {
+                argument,
+                message: error.to_string(),
+                line: error.line(),
+                column: error.column(),
+            },
+            _ => I18nError::argument(argument, error),
+        }
+    }
+
+    pub(crate) fn argument(argument: &'static str, error: impl fmt::Display) -> I18nError {
+        I18nError::InvalidArgument { argument, message: error.to_string() }
+    }
+
+    pub(crate) fn serialization(error: serde_json::Error) -> I18nError {
+        I18nError::Serialization { message: error.to_string() }
+    }
+}
+
+impl fmt::Display for I18nError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            I18nError::InvalidConfig { message, .. } => write!(f, "Invalid config: {}", message),
+            I18nError::InvalidCatalog { format, message, .. } => {
+                let label = match *format {
+                    "json" => "catalog",
+                    "po" => "PO",
+                    "mo" => "MO",
+                    "arb" => "ARB",
+                    "strings" => ".strings",
+                    "stringsdict" => ".stringsdict",
+                    "android" => "strings.xml",
+                    "properties" => ".properties",
+                    "ts" => ".ts",
+                    "resx" => "ResX",
+                    "tmx" => "TMX",
+                    "compiled" => "compiled catalog",
+                    "gzip" => "gzip data",
+                    "deflate" => "deflate data",
+                    other => other,
+                };
+                write!(f, "Invalid {}: {}", label, message)
+            }
+            I18nError::SyntaxError { argument, message, .. } | I18nError::InvalidArgument { argument, message } => {
+                write!(f, "Invalid {}: {}", argument, message)
+            }
+            I18nError::MissingKey { key, locale } => write!(f, "Missing key: {} ({})", key, locale),
+            I18nError::MissingVariable { name } => write!(f, "Missing value for {{{{{}}}}}", name),
+            I18nError::UnsupportedLocale { locale } => write!(f, "Unsupported locale: {}", locale),
+            I18nError::MergeConflict { keys } => write!(f, "Merge conflict: {}", keys.join(", ")),
+            I18nError::Serialization { message } => write!(f, "Serialization error: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for I18nError {}
+
+impl From<I18nError> for JsValue {
+    fn from(error: I18nError) -> JsValue {
+        let js = js_sys::Error::new(&error.to_string());
+        js.set_name(error.name());
+        let set = |field: &str, value: JsValue| {
+            let _ = js_sys::Reflect::set(&js, &field.into(), &value);
+        };
+        set("code", error.code().into());
+        match &error {
+            I18nError::InvalidConfig { warnings, .. } => {
+                if !warnings.is_empty() {
+                    set("warnings", serde_wasm_bindgen::to_value(warnings).unwrap_or(JsValue::UNDEFINED));
+                }
+            }
+            I18nError::Serialization { .. } => {}
+            I18nError::InvalidCatalog { format, line, column, .. } => {
+                set("format", (*format).into());
+                if let Some(line) = line {
+                    set("line", (*line as f64).into());
+                }
+                if let Some(column) = column {
+                    set("column", (*column as f64).into());
+                }
+            }
+            I18nError::SyntaxError { argument, line, column, .. } => {
+                set("argument", (*argument).into());
+                set("line", (*line as f64).into());
+                set("column", (*column as f64).into());
+            }
+            I18nError::InvalidArgument { argument, .. } => set("argument", (*argument).into()),
+            I18nError::MissingKey { key, locale } => {
+                set("key", key.as_str().into());
+                set("locale", locale.as_str().into());
+            }
+            I18nError::MissingVariable { name } => set("name", name.as_str().into()),
+            I18nError::UnsupportedLocale { locale } => set("locale", locale.as_str().into()),
+            I18nError::MergeConflict { keys } => {
+                set("keys", keys.iter().map(|key| JsValue::from_str(key)).collect::<js_sys::Array>().into());
+            }
+        }
+        js.into()
+    }
+}
@@ -0,0 +1,152 @@
+// C ABI - the engine for Deno FFI, bun:ffi and other native callers
+//
+// Built natively with `--features ffi`, which makes the shared library
+// (libi18n_wasm.so / .dylib / i18n_wasm.dll) export the functions below;
+// include/i18n.h declares them. Strings passed in are NUL-terminated UTF-8.
+// Translations are written to a caller buffer as snprintf does: the return
+// value is the full length in bytes, and when it is not below the capacity
+// the text was cut short (still NUL-terminated) and the call can be repeated
+// with a larger buffer. Negative returns are the error codes below.
+use std::ffi::{c_char, c_int, CStr};
+use std::ptr;
+
+use super::{I18nError, I18nWasm};
+
+pub const I18N_OK: c_int = 0;
+/// A null pointer or a string that is not UTF-8
+pub const I18N_INVALID_ARGUMENT: c_int = -1;
+/// The config or catalog is not valid JSON of the expected shape
+pub const I18N_INVALID_JSON: c_int = -2;
+/// No locale has the key and the instance is in strict mode
+pub const I18N_MISSING_KEY: c_int = -3;
+
+/// Create an instance from a JSON config, as `new I18nWasm(config)`.
+/// Returns null if the config is invalid.
+///
+/// # Safety
+///
+/// `config_json` must be null or a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn i18n_create(config_json: *const c_char) -> *mut I18nWasm {
+    match text(config_json).map(I18nWasm::new) {
+        Some(Ok(i18n)) => Box::into_raw(Box::new(i18n)),
+        _ => ptr::null_mut(),
+    }
+}
+
+/// Free an instance from i18n_create; null is ignored
+///
+/// # Safety
+///
+/// `instance` must be null or from i18n_create, and not used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn i18n_destroy(instance: *mut I18nWasm) {
+    if !instance.is_null() {
+        drop(Box::from_raw(instance));
+    }
+}
+
+/// Load a JSON catalog into the default namespace, as loadCatalog
+///
+/// # Safety
+///
+/// `instance` must be from i18n_create; the strings null or NUL-terminated.
+#[no_mangle]
+pub unsafe extern "C" fn i18n_load_catalog(
+    instance: *mut I18nWasm,
+    locale: *const c_char,
+    catalog_json: *const c_char,
+) -> c_int {
+    let (Some(i18n), Some(locale), Some(json)) = (instance.as_mut(), text(locale), text(catalog_json)) else {
+        return I18N_INVALID_ARGUMENT;
+    };
+    match i18n.load_catalog(locale, json) {
+        Ok(()) => I18N_OK,
+        Err(error) => code(&error),
+    }
+}
+
+/// Switch the current locale, as setLocale
+///
+/// # Safety
+///
+/// `instance` must be from i18n_create; `locale` null or NUL-terminated.
+#[no_mangle]
+pub unsafe extern "C" fn i18n_set_locale(instance: *mut I18nWasm, locale: *const c_char) -> c_int {
+    let (Some(i18n), Some(locale)) = (instance.as_mut(), text(locale)) else {
+        return I18N_INVALID_ARGUMENT;
+    };
+    i18n.set_locale(locale);
+    I18N_OK
+}
+
+/// Translate a key into `buffer`, as translate
+///
+/// # Safety
+///
+/// `instance` must be from i18n_create, `key` null or NUL-terminated and
+/// `buffer` valid for `capacity` bytes (it may be null when that is 0).
+#[no_mangle]
+pub unsafe extern "C" fn i18n_translate(
+    instance: *const I18nWasm,
+    key: *const c_char,
+    buffer: *mut c_char,
+    capacity: usize,
+) -> isize {
+    let (Some(i18n), Some(key)) = (instance.as_ref(), text(key)) else {
+        return I18N_INVALID_ARGUMENT as isize;
+    };
+    match i18n.translate(key) {
+        Ok(translation) => write(&translation, buffer, capacity),
+        Err(error) => code(&error) as isize,
+    }
+}
+
+/// Translate the plural form of a key for a count into `buffer`, as
+/// translatePlural
+///
+/// # Safety
+///
+/// As i18n_translate.
+#[no_mangle]
+pub unsafe extern "C" fn i18n_translate_plural(
+    instance: *const I18nWasm,
+    key: *const c_char,
+    count: f64,
+    buffer: *mut c_char,
+    capacity: usize,
+) -> isize {
+    let (Some(i18n), Some(key)) = (instance.as_ref(), text(key)) else {
+        return I18N_INVALID_ARGUMENT as isize;
+    };
+    match i18n.translate_plural(key, count) {
+        Ok(translation) => write(&translation, buffer, capacity),
+        Err(error) => code(&error) as isize,
+    }
+}
+
+/// The error code for an I18nError
+fn code(error: &I18nError) -> c_int {
+    match error {
+        I18nError::MissingKey { .. } => I18N_MISSING_KEY,
+        I18nError::InvalidConfig { .. } | I18nError::InvalidCatalog { .. } => I18N_INVALID_JSON,
+        _ => I18N_INVALID_ARGUMENT,
+    }
+}
+
+unsafe fn text<'a>(ptr: *const c_char) -> Option<&'a str> {
+    if ptr.is_null() {
+        return None;
+    }
+    CStr::from_ptr(ptr).to_str().ok()
+}
+
+/// Copy as much of `text` as fits, NUL-terminated; returns its full length
+unsafe fn write(text: &str, buffer: *mut c_char, capacity: usize) -> isize {
+    if !buffer.is_null() && capacity > 0 {
+        let length = text.len().min(capacity - 1);
+        ptr::copy_nonoverlapping(text.as_ptr().cast::<c_char>(), buffer, length);
+        *buffer.add(length) = 0;
+    }
+    text.len() as isize
+}
@@ -0,0 +1,95 @@
+// Value formatters for {{name, format}} placeholders
+//
+// The format is a name with an optional argument in parentheses:
+// {{n, number}}, {{amount, currency(EUR)}}, {{when, relativeTime}} (a
+// difference in seconds) or {{when, relativeTime(day)}} (in that unit).
+// Formatters registered with registerFormatter are tried first, so a built-in
+// can be replaced; they are called as fn(value, locale, argument). A format
+// that does not apply to the value leaves it written as it is.
+use std::collections::HashMap;
+
+use serde::Serialize;
+use serde_json::Value;
+use wasm_bindgen::JsValue;
+
+use super::{numbers, RelativeTimeFormat, TimeUnit};
+
+/// JS formatters by name
+pub(crate) type Formatters = HashMap<String, js_sys::Function>;
+
+/// Currencies written with a symbol rather than their ISO code
+const CURRENCY_SYMBOLS: &[(&str, &str)] = &[
+    ("EUR", "€"),
+    ("GBP", "£"),
+    ("INR", "₹"),
+    ("JPY", "¥"),
+    ("KRW", "₩"),
+    ("USD", "$"),
+];
+
+/// Currencies without minor units
+const ZERO_DECIMAL_CURRENCIES: &[&str] = &["JPY", "KRW", "VND", "CLP", "ISK"];
+
+/// The value in a format for a locale, or None when the format is unknown or
+/// does not apply
+pub(crate) fn apply(format: &str, value: &Value, locale: &str, registered: Option<&Formatters>) -> Option<String> {
+    let (name, argument) = match format.split_once('(') {
+        Some((name, rest)) => (name.trim(), Some(rest.trim_end_matches(')').trim())),
+        None => (format.trim(), None),
+    };
+    if let Some(formatter) = registered.and_then(|registered| registered.get(name)) {
+        let serializer = serde_wasm_bindgen::Serializer::json_compatible();
+        let value = value.serialize(&serializer).ok()?;
+        let argument = argument.map_or(JsValue::UNDEFINED, JsValue::from_str);
+        return formatter.call3(&JsValue::NULL, &value, &JsValue::from_str(locale), &argument).ok()?.as_string();
+    }
+
+    let number = value.as_f64()?;
+    match name.to_ascii_lowercase().as_str() {
+        "number" => Some(numbers::format(&number.to_string(), locale)),
+        "currency" => Some(currency(number, argument, locale)),
+        "relativetime" => {
+            let formatter = RelativeTimeFormat::new(locale);
+            Some(match argument.and_then(time_unit) {
+                Some(unit) => formatter.format_unit(number, unit),
+                None => formatter.format(number),
+            })
+        }
+        _ => None,
+    }
+}
+
+/// An amount in a currency: two decimals (none for yen and the like), the
+/// symbol before the number, or after it for locales with a decimal comma
+/// ("$1,234.50", "1.234,50 €"); codes without a symbol are written as is
+fn currency(amount: f64, code: Option<&str>, locale: &str) -> String {
+    let code = code.unwrap_or_default().to_ascii_uppercase();
+    let decimals = if ZERO_DECIMAL_CURRENCIES.contains(&code.as_str()) { 0 } else { 2 };
+    let number = numbers::format(&format!("{:.*}", decimals, amount), locale);
+    let symbol = CURRENCY_SYMBOLS
+        .iter()
+        .find(|(candidate, _)| *candidate == code)
+        .map_or(code.as_str(), |(_, symbol)| symbol);
+    match symbol {
+        "" => number,
+        _ if numbers::symbols(locale).decimal == "," => format!("{}\u{a0}{}", number, symbol),
+        _ if symbol.len() == 3 && symbol.is_ascii() => format!("{}\u{a0}{}", symbol, number),
+        _ => match number.strip_prefix('-') {
+            Some(number) => format!("-{}{}", symbol, number),
+            None => format!("{}{}", symbol, number),
+        },
+    }
+}
+
+fn time_unit(name: &str) -> Option<TimeUnit> {
+    Some(match name.trim_end_matches('s') {
+        "second" => TimeUnit::Second,
+        "minute" => TimeUnit::Minute,
+        "hour" => TimeUnit::Hour,
+        "day" => TimeUnit::Day,
+        "week" => TimeUnit::Week,
+        "month" => TimeUnit::Month,
+        "year" => TimeUnit::Year,
+        _ => return None,
+    })
+}
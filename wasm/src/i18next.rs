@@ -0,0 +1,112 @@
+// i18next JSON catalog conventions
+//
+// Plurals are sibling keys with category suffixes ("item_one", "item_other");
+// a "_zero" form also covers a count of exactly 0, as in i18next. A context
+// is a "_male"/"_female"-style suffix on a key whose base also exists; it is
+// kept as written and also stored as "key@male" so context lookups find it.
+// Messages use {{name}} interpolation ({{name, format}} reads the same
+// value, {{- name}} writes it unescaped) and $t(key) or $t(key, {"count": 2})
+// nesting.
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use serde_json::{Map, Value};
+
+use super::arb::SELECT_SEPARATOR;
+use super::template::{Interpolation, Template};
+use super::{Catalog, I18nError, PluralForms};
+
+const PLURAL_SUFFIXES: [&str; 6] = ["zero", "one", "two", "few", "many", "other"];
+
+/// Nested $t() references resolved before giving up on a cycle
+pub(crate) const MAX_NESTING_DEPTH: usize = 10;
+
+/// Group suffixed plural keys into plural blocks and add "key@context" aliases
+pub(crate) fn fold(catalog: &mut Catalog) {
+    let mut groups: HashMap<String, Map<String, Value>> = HashMap::new();
+    for (key, text) in &catalog.strings {
+        if let Some((base, category)) = key.rsplit_once('_') {
+            if PLURAL_SUFFIXES.contains(&category) && !base.ends_with("_ordinal") {
+                groups
+                    .entry(base.to_string())
+                    .or_default()
+                    .insert(category.to_string(), Value::String(text.to_string()));
+            }
+        }
+    }
+
+    for (base, mut forms) in groups {
+        if let Some(zero) = forms.get("zero").cloned() {
+            forms.insert("=0".to_string(), zero);
+        }
+        let Some(plural) = PluralForms::from_object(&forms) else {
+            continue;
+        };
+        for category in PLURAL_SUFFIXES {
+            catalog.strings.remove(&format!("{}_{}", base, category));
+        }
+        catalog.plurals.insert(base, plural);
+    }
+
+    let context_alias = |key: &str| {
+        let (base, context) = key.rsplit_once('_')?;
+        let has_base = catalog.strings.contains_key(base) || catalog.plurals.contains_key(base);
+        (has_base && !context.is_empty()).then(|| format!("{}{}{}", base, SELECT_SEPARATOR, context))
+    };
+    let string_aliases: Vec<(String, Arc<str>)> = catalog
+        .strings
+        .iter()
+        .filter_map(|(key, text)| Some((context_alias(key)?, text.clone())))
+        .collect();
+    let plural_aliases: Vec<(String, PluralForms)> = catalog
+        .plurals
+        .iter()
+        .filter_map(|(key, forms)| Some((context_alias(key)?, forms.clone())))
+        .collect();
+    catalog.strings.extend(string_aliases);
+    catalog.plurals.extend(plural_aliases);
+}
+
+/// Replace {{name}} placeholders with option values; unknown names are
+/// handled as interpolation.missing says
+pub(crate) fn interpolate(template: &str, values: &Map<String, Value>, interpolation: Interpolation) -> Result<String, I18nError> {
+    Template::parse(template).render(None, values, interpolation)
+}
+
+/// Replace each $t(key) or $t(key, {options}) with resolve(key, options)
+pub(crate) fn nest(text: &str, mut resolve: impl FnMut(&str, Map<String, Value>) -> String) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("$t(") {
+        let Some(end) = closing_paren(&rest[start + 3..]).map(|offset| start + 3 + offset) else {
+            break;
+        };
+        let call = &rest[start + 3..end];
+        let (key, options) = match call.split_once(',') {
+            Some((key, options)) => match serde_json::from_str::<Map<String, Value>>(options.trim()) {
+                Ok(options) => (key.trim(), options),
+                Err(_) => (call.trim(), Map::new()),
+            },
+            None => (call.trim(), Map::new()),
+        };
+        result.push_str(&rest[..start]);
+        result.push_str(&resolve(key, options));
+        rest = &rest[end + 1..];
+    }
+    result.push_str(rest);
+    result
+}
+
+/// Offset of the ')' closing a $t( call, allowing parentheses in its options
+fn closing_paren(text: &str) -> Option<usize> {
+    let mut depth = 0;
+    for (index, c) in text.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' if depth == 0 => return Some(index),
+            ')' => depth -= 1,
+            _ => {}
+        }
+    }
+    None
+}
@@ -0,0 +1,305 @@
+// DEFLATE decompression for compressed catalogs
+//
+// gzip (RFC 1952) and zlib (RFC 1950, HTTP's "deflate") wrappers around
+// DEFLATE (RFC 1951) data: stored, fixed-Huffman and dynamic-Huffman blocks.
+// Huffman codes are decoded canonically from per-length counts, as zlib's
+// puff does, trading a little speed for no lookup tables. The gzip CRC-32
+// and length and the zlib Adler-32 are checked.
+
+const MAX_BITS: usize = 15;
+
+/// Length codes 257..285: base length and extra bits
+const LENGTH_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131, 163, 195, 227, 258,
+];
+const LENGTH_EXTRA: [u8; 29] = [0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0];
+/// Distance codes 0..29: base distance and extra bits
+const DISTANCE_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537, 2049, 3073, 4097, 6145,
+    8193, 12289, 16385, 24577,
+];
+const DISTANCE_EXTRA: [u8; 30] = [0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13, 13];
+/// Order in which dynamic blocks list code-length code lengths
+const CODE_LENGTH_ORDER: [usize; 19] = [16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15];
+
+/// Decompress a gzip member
+pub(crate) fn gunzip(bytes: &[u8]) -> Result<Vec<u8>, String> {
+    const FHCRC: u8 = 2;
+    const FEXTRA: u8 = 4;
+    const FNAME: u8 = 8;
+    const FCOMMENT: u8 = 16;
+
+    if bytes.len() < 18 || bytes[..3] != [0x1f, 0x8b, 8] {
+        return Err("not gzip data".to_string());
+    }
+    let flags = bytes[3];
+    let mut offset = 10;
+    if flags & FEXTRA != 0 {
+        let length = bytes.get(offset..offset + 2).ok_or("truncated header")?;
+        offset += 2 + u16::from_le_bytes([length[0], length[1]]) as usize;
+    }
+    for flag in [FNAME, FCOMMENT] {
+        if flags & flag != 0 {
+            let end = bytes.get(offset..).and_then(|rest| rest.iter().position(|b| *b == 0)).ok_or("truncated header")?;
+            offset += end + 1;
+        }
+    }
+    if flags & FHCRC != 0 {
+        offset += 2;
+    }
+
+    let data = bytes.get(offset..).ok_or("truncated header")?;
+    let (out, used) = inflate(data)?;
+    let trailer = data.get(used..used + 8).ok_or("missing gzip trailer")?;
+    if u32::from_le_bytes(trailer[..4].try_into().unwrap()) != crc32(&out) {
+        return Err("CRC-32 mismatch".to_string());
+    }
+    if u32::from_le_bytes(trailer[4..].try_into().unwrap()) != out.len() as u32 {
+        return Err("length mismatch".to_string());
+    }
+    Ok(out)
+}
+
+/// Decompress a zlib stream
+pub(crate) fn unzlib(bytes: &[u8]) -> Result<Vec<u8>, String> {
+    let header = bytes.get(..2).ok_or("not zlib data")?;
+    if header[0] & 0x0f != 8 || u16::from_be_bytes([header[0], header[1]]) % 31 != 0 {
+        return Err("not zlib data".to_string());
+    }
+    if header[1] & 0x20 != 0 {
+        return Err("preset dictionaries are not supported".to_string());
+    }
+    let (out, used) = inflate(&bytes[2..])?;
+    let trailer = bytes.get(2 + used..2 + used + 4).ok_or("missing zlib trailer")?;
+    if u32::from_be_bytes(trailer.try_into().unwrap()) != adler32(&out) {
+        return Err("Adler-32 mismatch".to_string());
+    }
+    Ok(out)
+}
+
+/// Inflate raw DEFLATE data; returns the output and the bytes consumed
+fn inflate(data: &[u8]) -> Result<(Vec<u8>, usize), String> {
+    let mut input = Bits::new(data);
+    let mut out = Vec::with_capacity(data.len() * 4);
+    loop {
+        let last = input.bits(1)? == 1;
+        match input.bits(2)? {
+            0 => stored(&mut input, &mut out)?,
+            1 => {
+                let (lengths, distances) = fixed_codes();
+                codes(&mut input, &mut out, &lengths, &distances)?;
+            }
+            2 => {
+                let (lengths, distances) = dynamic_codes(&mut input)?;
+                codes(&mut input, &mut out, &lengths, &distances)?;
+            }
+            _ => return Err("invalid block type".to_string()),
+        }
+        if last {
+            return Ok((out, input.offset));
+        }
+    }
+}
+
+fn stored(input: &mut Bits, out: &mut Vec<u8>) -> Result<(), String> {
+    // Stored blocks start on a byte boundary
+    input.align();
+    let header = input.bytes(4).map_err(|_| "truncated stored block")?;
+    let length = u16::from_le_bytes([header[0], header[1]]);
+    if length != !u16::from_le_bytes([header[2], header[3]]) {
+        return Err("stored block length mismatch".to_string());
+    }
+    out.extend_from_slice(input.bytes(length as usize).map_err(|_| "truncated stored block")?);
+    Ok(())
+}
+
+fn fixed_codes() -> (Huffman, Huffman) {
+    let mut lengths = [0u8; 288];
+    lengths[..144].fill(8);
+    lengths[144..256].fill(9);
+    lengths[256..280].fill(7);
+    lengths[280..].fill(8);
+    // Complete codes by construction
+    (Huffman::new(&lengths).unwrap(), Huffman::new(&[5; 30]).unwrap())
+}
+
+fn dynamic_codes(input: &mut Bits) -> Result<(Huffman, Huffman), String> {
+    let literal_count = input.bits(5)? as usize + 257;
+    let distance_count = input.bits(5)? as usize + 1;
+    let code_length_count = input.bits(4)? as usize + 4;
+    if literal_count > 286 || distance_count > 30 {
+        return Err("too many codes".to_string());
+    }
+
+    let mut code_lengths = [0u8; 19];
+    for index in CODE_LENGTH_ORDER.into_iter().take(code_length_count) {
+        code_lengths[index] = input.bits(3)? as u8;
+    }
+    let code_length_code = Huffman::new(&code_lengths)?;
+
+    let mut lengths = vec![0u8; literal_count + distance_count];
+    let mut index = 0;
+    while index < lengths.len() {
+        let symbol = code_length_code.decode(input)?;
+        let (value, repeat) = match symbol {
+            0..=15 => (symbol as u8, 1),
+            16 => {
+                let previous = *lengths[..index].last().ok_or("repeat with no previous length")?;
+                (previous, 3 + input.bits(2)? as usize)
+            }
+            17 => (0, 3 + input.bits(3)? as usize),
+            _ => (0, 11 + input.bits(7)? as usize),
+        };
+        let end = index + repeat;
+        lengths.get_mut(index..end).ok_or("too many code lengths")?.fill(value);
+        index = end;
+    }
+    if lengths[256] == 0 {
+        return Err("no end-of-block code".to_string());
+    }
+    Ok((Huffman::new(&lengths[..literal_count])?, Huffman::new(&lengths[literal_count..])?))
+}
+
+fn codes(input: &mut Bits, out: &mut Vec<u8>, lengths: &Huffman, distances: &Huffman) -> Result<(), String> {
+    loop {
+        let symbol = lengths.decode(input)? as usize;
+        match symbol {
+            0..=255 => out.push(symbol as u8),
+            256 => return Ok(()),
+            _ => {
+                let code = symbol - 257;
+                let base = *LENGTH_BASE.get(code).ok_or("invalid length code")? as usize;
+                let length = base + input.bits(LENGTH_EXTRA[code])? as usize;
+                let code = distances.decode(input)? as usize;
+                let base = *DISTANCE_BASE.get(code).ok_or("invalid distance code")? as usize;
+                let distance = base + input.bits(DISTANCE_EXTRA[code])? as usize;
+                if distance > out.len() {
+                    return Err("distance before start of output".to_string());
+                }
+                // Copies may overlap what they write ("abcabcabc" from "abc")
+                let start = out.len() - distance;
+                for index in start..start + length {
+                    out.push(out[index]);
+                }
+            }
+        }
+    }
+}
+
+/// LSB-first bit reader over the compressed bytes (shared with brotli)
+pub(crate) struct Bits<'a> {
+    data: &'a [u8],
+    offset: usize,
+    buffer: u32,
+    count: u8,
+}
+
+impl<'a> Bits<'a> {
+    pub(crate) fn new(data: &'a [u8]) -> Bits<'a> {
+        Bits { data, offset: 0, buffer: 0, count: 0 }
+    }
+
+    /// Drop the rest of the current byte
+    pub(crate) fn align(&mut self) {
+        self.buffer = 0;
+        self.count = 0;
+    }
+
+    /// The next `length` whole bytes; call align() first
+    pub(crate) fn bytes(&mut self, length: usize) -> Result<&'a [u8], String> {
+        let bytes = self.data.get(self.offset..self.offset + length).ok_or("unexpected end of data")?;
+        self.offset += length;
+        Ok(bytes)
+    }
+
+    pub(crate) fn bits(&mut self, needed: u8) -> Result<u32, String> {
+        while self.count < needed {
+            let byte = *self.data.get(self.offset).ok_or("unexpected end of data")?;
+            self.buffer |= (byte as u32) << self.count;
+            self.offset += 1;
+            self.count += 8;
+        }
+        let value = self.buffer & ((1u32 << needed) - 1);
+        self.buffer >>= needed;
+        self.count -= needed;
+        Ok(value)
+    }
+}
+
+/// Canonical Huffman code: how many codes of each length, and the symbols
+/// in code order
+pub(crate) struct Huffman {
+    counts: [u16; MAX_BITS + 1],
+    symbols: Vec<u16>,
+}
+
+impl Huffman {
+    pub(crate) fn new(lengths: &[u8]) -> Result<Huffman, String> {
+        let mut counts = [0u16; MAX_BITS + 1];
+        for &length in lengths {
+            counts[length as usize] += 1;
+        }
+        // Reject over-subscribed codes; incomplete ones are allowed (a
+        // single distance code is common)
+        let mut left: i32 = 1;
+        for &count in &counts[1..] {
+            left = (left << 1) - count as i32;
+            if left < 0 {
+                return Err("over-subscribed Huffman code".to_string());
+            }
+        }
+
+        let mut offsets = [0u16; MAX_BITS + 2];
+        for length in 1..=MAX_BITS {
+            offsets[length + 1] = offsets[length] + counts[length];
+        }
+        let mut symbols = vec![0u16; offsets[MAX_BITS + 1] as usize];
+        for (symbol, &length) in lengths.iter().enumerate() {
+            if length != 0 {
+                symbols[offsets[length as usize] as usize] = symbol as u16;
+                offsets[length as usize] += 1;
+            }
+        }
+        Ok(Huffman { counts, symbols })
+    }
+
+    pub(crate) fn decode(&self, input: &mut Bits) -> Result<u16, String> {
+        // Codes are read bit by bit, most significant first
+        let (mut code, mut first, mut index) = (0i32, 0i32, 0i32);
+        for &count in &self.counts[1..] {
+            code |= input.bits(1)? as i32;
+            let count = count as i32;
+            if code - first < count {
+                return Ok(self.symbols[(index + code - first) as usize]);
+            }
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+        Err("invalid Huffman code".to_string())
+    }
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = (crc >> 1) ^ (0xedb8_8320 & (crc & 1).wrapping_neg());
+        }
+    }
+    !crc
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for chunk in data.chunks(5552) {
+        for &byte in chunk {
+            a += byte as u32;
+            b += a;
+        }
+        a %= 65521;
+        b %= 65521;
+    }
+    (b << 16) | a
+}
@@ -0,0 +1,60 @@
+// String interning for catalog values
+//
+// Every string value stored in a catalog goes through one table (per thread,
+// which in wasm is the whole module), so a brand name, URL or number repeated
+// across locales, namespaces and instances is held once. Entries nothing but
+// the table refers to any more are dropped by prune.
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use serde::Serialize;
+
+thread_local! {
+    static TABLE: RefCell<HashSet<Arc<str>>> = RefCell::new(HashSet::new());
+}
+
+/// The shared copy of a value, adding it to the table if it is new
+pub(crate) fn intern(text: &Arc<str>) -> Arc<str> {
+    TABLE.with(|table| {
+        let mut table = table.borrow_mut();
+        if let Some(shared) = table.get(&**text) {
+            return shared.clone();
+        }
+        table.insert(text.clone());
+        text.clone()
+    })
+}
+
+/// Forget values no catalog holds any more
+pub(crate) fn prune() {
+    TABLE.with(|table| table.borrow_mut().retain(|text| Arc::strong_count(text) > 1));
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct MemoryStats {
+    /// Distinct values held
+    strings: usize,
+    /// UTF-8 bytes of the distinct values
+    bytes: usize,
+    /// Catalog entries pointing at them
+    references: usize,
+    /// Bytes the entries would take as separate copies, less bytes
+    saved_bytes: usize,
+}
+
+pub(crate) fn stats() -> MemoryStats {
+    prune();
+    TABLE.with(|table| {
+        let table = table.borrow();
+        let mut stats = MemoryStats { strings: table.len(), bytes: 0, references: 0, saved_bytes: 0 };
+        for text in table.iter() {
+            let references = Arc::strong_count(text) - 1;
+            stats.bytes += text.len();
+            stats.references += references;
+            stats.saved_bytes += (references - 1) * text.len();
+        }
+        stats
+    })
+}
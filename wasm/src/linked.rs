@@ -0,0 +1,106 @@
+// Linked messages - vue-i18n's @:key references inside message values
+//
+// "@:brand" is replaced by the message of another key, so a shared fragment
+// such as a product name lives in one place; "@:(brand)" delimits a key that
+// is followed by text that would otherwise read as part of it. Keys take
+// letters, digits and "_-.:/", without trailing punctuation, so "Try
+// @:brand." links "brand". i18next's $t(key) form is handled in i18next.rs.
+//
+// A modifier changes the case of the linked text: "@:(brand, upper)" or, as
+// vue-i18n writes it, "@.upper:brand", with upper, lower and capitalize
+// (first letter only). Casing follows the locale: Turkish and Azerbaijani
+// dotted and dotless i, Dutch "ij" capitalized as one letter.
+
+/// Replace each @:key, @:(key, modifier) or @.modifier:key with
+/// resolve(key), cased for the locale as the modifier says
+pub(crate) fn expand(text: &str, locale: &str, mut resolve: impl FnMut(&str) -> String) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('@') {
+        result.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let (modifier, tail) = match after.strip_prefix('.').and_then(|dotted| dotted.split_once(':')) {
+            Some((modifier, tail)) if !modifier.is_empty() && modifier.chars().all(|c| c.is_ascii_alphabetic()) => {
+                (Some(modifier), tail)
+            }
+            _ => match after.strip_prefix(':') {
+                Some(tail) => (None, tail),
+                None => {
+                    result.push('@');
+                    rest = after;
+                    continue;
+                }
+            },
+        };
+        let (key, modifier, consumed) = match tail.strip_prefix('(') {
+            Some(inner) => match inner.find(')') {
+                Some(end) => match inner[..end].split_once(',') {
+                    Some((key, modifier)) => (key.trim(), Some(modifier.trim()), end + 2),
+                    None => (inner[..end].trim(), modifier, end + 2),
+                },
+                None => ("", modifier, 0),
+            },
+            None => {
+                let end = tail
+                    .find(|c: char| !(c.is_alphanumeric() || "_-.:/".contains(c)))
+                    .unwrap_or(tail.len());
+                let key = tail[..end].trim_end_matches(['.', ':', '-', '/']);
+                (key, modifier, key.len())
+            }
+        };
+        if key.is_empty() {
+            result.push('@');
+            rest = after;
+            continue;
+        }
+        let linked = resolve(key);
+        match modifier {
+            Some(modifier) => result.push_str(&apply(modifier, &linked, locale)),
+            None => result.push_str(&linked),
+        }
+        rest = &tail[consumed..];
+    }
+    result.push_str(rest);
+    result
+}
+
+/// Text with a case modifier applied; unknown modifiers leave it unchanged
+pub(crate) fn apply(modifier: &str, text: &str, locale: &str) -> String {
+    let language = locale.split(['-', '_']).next().unwrap_or_default().to_ascii_lowercase();
+    match modifier {
+        "upper" => upper(text, &language),
+        "lower" => lower(text, &language),
+        "capitalize" => capitalize(text, &language),
+        _ => text.to_string(),
+    }
+}
+
+fn dotted_i(language: &str) -> bool {
+    matches!(language, "tr" | "az")
+}
+
+fn upper(text: &str, language: &str) -> String {
+    match dotted_i(language) {
+        true => text.replace('i', "İ").to_uppercase(),
+        false => text.to_uppercase(),
+    }
+}
+
+fn lower(text: &str, language: &str) -> String {
+    match dotted_i(language) {
+        true => text.replace('I', "ı").replace('İ', "i").to_lowercase(),
+        false => text.to_lowercase(),
+    }
+}
+
+fn capitalize(text: &str, language: &str) -> String {
+    let mut chars = text.chars();
+    let Some(first) = chars.next() else {
+        return String::new();
+    };
+    let rest = chars.as_str();
+    if language == "nl" && first.eq_ignore_ascii_case(&'i') && rest.starts_with(['j', 'J']) {
+        return format!("IJ{}", &rest[1..]);
+    }
+    format!("{}{}", upper(first.encode_utf8(&mut [0; 4]), language), rest)
+}
@@ -0,0 +1,281 @@
+// Message syntax linting - ICU MessageFormat, mustache and sprintf templates
+//
+// Each linter walks one message and reports every problem it can recover
+// from, with the byte offset where it starts:
+//   icu:      unbalanced braces, bad argument names or types, plural/select
+//             cases without a body or an "other" case, invalid plural
+//             selectors; apostrophe quoting ('{', '') is honoured
+//   mustache: unclosed {{, empty {{}} and stray }}
+//   sprintf:  unknown conversions (a literal % must be %%) and mixing
+//             positional %1$s with sequential %s
+use serde::Serialize;
+
+use super::placeholders;
+
+#[derive(Debug, PartialEq, Serialize)]
+pub(crate) struct LintError {
+    pub offset: usize,
+    pub message: String,
+}
+
+/// ICU argument types other than plural, select and selectordinal
+const SIMPLE_TYPES: [&str; 6] = ["number", "date", "time", "spellout", "ordinal", "duration"];
+
+const PLURAL_SELECTORS: [&str; 6] = ["zero", "one", "two", "few", "many", "other"];
+
+pub(crate) fn lint_icu(message: &str) -> Vec<LintError> {
+    let mut parser = IcuParser {
+        text: message,
+        pos: 0,
+        errors: Vec::new(),
+    };
+    parser.message(false);
+    parser.errors
+}
+
+struct IcuParser<'a> {
+    text: &'a str,
+    pos: usize,
+    errors: Vec<LintError>,
+}
+
+impl IcuParser<'_> {
+    fn error(&mut self, offset: usize, message: impl Into<String>) {
+        self.errors.push(LintError {
+            offset,
+            message: message.into(),
+        });
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.text.as_bytes().get(self.pos).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(|b| b.is_ascii_whitespace()) {
+            self.pos += 1;
+        }
+    }
+
+    fn word(&mut self, accept: impl Fn(u8) -> bool) -> &str {
+        let start = self.pos;
+        while self.peek().is_some_and(&accept) {
+            self.pos += 1;
+        }
+        &self.text[start..self.pos]
+    }
+
+    /// Message text up to an unmatched '}' (nested) or the end (top level)
+    fn message(&mut self, nested: bool) {
+        while let Some(byte) = self.peek() {
+            match byte {
+                b'{' => self.argument(),
+                b'}' if nested => return,
+                b'}' => {
+                    self.error(self.pos, "unmatched '}'");
+                    self.pos += 1;
+                }
+                b'\'' => self.quoted(),
+                _ => self.pos += 1,
+            }
+        }
+    }
+
+    /// '' is an apostrophe; '{...' quotes syntax characters up to the next '
+    fn quoted(&mut self) {
+        match self.text.as_bytes().get(self.pos + 1) {
+            Some(b'\'') => self.pos += 2,
+            Some(b'{' | b'}' | b'#' | b'|') => {
+                let close = self.text[self.pos + 1..].find('\'');
+                self.pos = close.map_or(self.text.len(), |close| self.pos + close + 2);
+            }
+            _ => self.pos += 1,
+        }
+    }
+
+    fn argument(&mut self) {
+        let open = self.pos;
+        self.pos += 1;
+        self.skip_whitespace();
+        let name_start = self.pos;
+        if self.word(|b| b.is_ascii_alphanumeric() || b == b'_').is_empty() {
+            self.error(name_start, "expected an argument name");
+        }
+        self.skip_whitespace();
+
+        match self.peek() {
+            Some(b'}') => self.pos += 1,
+            Some(b',') => {
+                self.pos += 1;
+                self.skip_whitespace();
+                let type_start = self.pos;
+                let kind = self.word(|b| b.is_ascii_alphabetic()).to_string();
+                self.skip_whitespace();
+                match kind.as_str() {
+                    "plural" | "selectordinal" | "select" => self.cases(open, &kind),
+                    _ => {
+                        if !SIMPLE_TYPES.contains(&kind.as_str()) {
+                            self.error(type_start, format!("unknown argument type '{}'", kind));
+                        }
+                        self.style(open);
+                    }
+                }
+            }
+            None => self.error(open, "unclosed '{'"),
+            _ => {
+                self.error(self.pos, "expected ',' or '}' after the argument name");
+                self.style(open);
+            }
+        }
+    }
+
+    /// Skip a `, style}` tail through the argument's closing brace
+    fn style(&mut self, open: usize) {
+        let mut depth = 0;
+        while let Some(byte) = self.peek() {
+            self.pos += 1;
+            match byte {
+                b'{' => depth += 1,
+                b'}' if depth == 0 => return,
+                b'}' => depth -= 1,
+                _ => {}
+            }
+        }
+        self.error(open, "unclosed '{'");
+    }
+
+    fn cases(&mut self, open: usize, kind: &str) {
+        if self.peek() != Some(b',') {
+            self.error(self.pos, format!("expected ',' after '{}'", kind));
+        } else {
+            self.pos += 1;
+        }
+        self.skip_whitespace();
+        if kind != "select" && self.text[self.pos..].starts_with("offset:") {
+            self.pos += "offset:".len();
+            self.skip_whitespace();
+            let offset_start = self.pos;
+            if self.word(|b| b.is_ascii_digit()).is_empty() {
+                self.error(offset_start, "expected a number after 'offset:'");
+            }
+        }
+
+        let mut has_other = false;
+        loop {
+            self.skip_whitespace();
+            match self.peek() {
+                None => {
+                    self.error(open, "unclosed '{'");
+                    return;
+                }
+                Some(b'}') => {
+                    self.pos += 1;
+                    break;
+                }
+                _ => {}
+            }
+
+            let selector_start = self.pos;
+            let selector = self.word(|b| !b.is_ascii_whitespace() && b != b'{' && b != b'}').to_string();
+            if selector.is_empty() {
+                self.error(selector_start, "expected a case selector");
+                self.pos += 1;
+                continue;
+            }
+            has_other |= selector == "other";
+            let valid = kind == "select"
+                || PLURAL_SELECTORS.contains(&selector.as_str())
+                || selector.strip_prefix('=').is_some_and(|n| n.parse::<f64>().is_ok());
+            if !valid {
+                self.error(selector_start, format!("invalid {} selector '{}'", kind, selector));
+            }
+
+            self.skip_whitespace();
+            if self.peek() != Some(b'{') {
+                self.error(self.pos, format!("expected '{{' after '{}'", selector));
+                continue;
+            }
+            let case_open = self.pos;
+            self.pos += 1;
+            self.message(true);
+            if self.peek() == Some(b'}') {
+                self.pos += 1;
+            } else {
+                self.error(case_open, "unclosed '{'");
+                return;
+            }
+        }
+
+        if !has_other {
+            self.error(open, format!("{} has no 'other' case", kind));
+        }
+    }
+}
+
+pub(crate) fn lint_mustache(message: &str) -> Vec<LintError> {
+    let mut errors = Vec::new();
+    let mut pos = 0;
+    while pos < message.len() {
+        let rest = &message[pos..];
+        if let Some(inner) = rest.strip_prefix("{{") {
+            match inner.find("}}") {
+                Some(end) if inner[..end].contains("{{") => {
+                    errors.push(LintError { offset: pos, message: "unclosed '{{'".to_string() });
+                    pos += 2;
+                    continue;
+                }
+                Some(end) => {
+                    if inner[..end].trim().trim_start_matches('-').trim().is_empty() {
+                        errors.push(LintError { offset: pos, message: "empty placeholder".to_string() });
+                    }
+                    pos += end + 4;
+                }
+                None => {
+                    errors.push(LintError { offset: pos, message: "unclosed '{{'".to_string() });
+                    break;
+                }
+            }
+        } else if rest.starts_with("}}") {
+            errors.push(LintError { offset: pos, message: "unmatched '}}'".to_string() });
+            pos += 2;
+        } else {
+            pos += rest.chars().next().map_or(1, char::len_utf8);
+        }
+    }
+    errors
+}
+
+pub(crate) fn lint_sprintf(message: &str) -> Vec<LintError> {
+    let mut errors = Vec::new();
+    let (mut positional, mut sequential) = (false, false);
+    let mut pos = 0;
+    while let Some(percent) = message[pos..].find('%').map(|offset| pos + offset) {
+        let tail = &message[percent..];
+        if tail.starts_with("%%") {
+            pos = percent + 2;
+            continue;
+        }
+        match placeholders::printf_conversion(tail) {
+            Some((index, _, length)) => {
+                let mixed = if index.is_some() { sequential } else { positional };
+                positional |= index.is_some();
+                sequential |= index.is_none();
+                if mixed {
+                    errors.push(LintError {
+                        offset: percent,
+                        message: "positional and sequential placeholders mixed".to_string(),
+                    });
+                }
+                pos = percent + length;
+            }
+            None => {
+                errors.push(LintError {
+                    offset: percent,
+                    message: "invalid conversion; write a literal % as %%".to_string(),
+                });
+                pos = percent + 1;
+            }
+        }
+    }
+    errors
+}
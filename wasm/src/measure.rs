@@ -0,0 +1,134 @@
+// Measurement systems and unit conversion for formatMeasurement
+//
+// The system comes from the locale's -u-ms- keyword ("metric", "ussystem",
+// "uksystem") or its region, following CLDR's measurementData and
+// unitPreferences: the US, Liberia and Myanmar use US customary units,
+// the UK keeps miles on the road, and a few more regions use Fahrenheit.
+// A tag without a region takes the likely one for its language (en is US).
+// Measurements are given in metric base units: meters, kilograms and
+// degrees Celsius.
+
+/// (unit, kind, factor to the kind's base unit)
+const UNITS: &[(&str, &str, f64)] = &[
+    ("kilometer", "length", 1000.0),
+    ("meter", "length", 1.0),
+    ("centimeter", "length", 0.01),
+    ("mile", "length", 1609.344),
+    ("foot", "length", 0.3048),
+    ("inch", "length", 0.0254),
+    ("kilogram", "mass", 1.0),
+    ("gram", "mass", 0.001),
+    ("pound", "mass", 0.453_592_37),
+    ("celsius", "temperature", 1.0),
+    ("fahrenheit", "temperature", 5.0 / 9.0),
+    ("liter", "volume", 1.0),
+    ("byte", "digital", 1.0),
+    ("kilobyte", "digital", 1e3),
+    ("megabyte", "digital", 1e6),
+    ("gigabyte", "digital", 1e9),
+    ("second", "duration", 1.0),
+    ("minute", "duration", 60.0),
+    ("hour", "duration", 3600.0),
+    ("day", "duration", 86400.0),
+];
+
+/// Regions on US customary units
+const US_SYSTEM: &[&str] = &["US", "LR", "MM"];
+
+/// Regions that measure roads in miles but are otherwise metric
+const UK_SYSTEM: &[&str] = &["GB"];
+
+/// Regions that give temperatures in Fahrenheit
+const FAHRENHEIT: &[&str] = &["US", "BS", "BZ", "KY", "LR", "PR", "PW"];
+
+/// Likely regions for languages written without one, where they change the
+/// system
+const LIKELY_REGIONS: &[(&str, &str)] = &[("en", "US"), ("my", "MM")];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum System {
+    Metric,
+    Us,
+    Uk,
+}
+
+impl System {
+    pub fn name(self) -> &'static str {
+        match self {
+            System::Metric => "metric",
+            System::Us => "ussystem",
+            System::Uk => "uksystem",
+        }
+    }
+}
+
+/// The locale's region, or its language's likely one
+fn region(tag: &str) -> Option<String> {
+    let mut subtags = tag.split("-u-").next().unwrap_or_default().split('-');
+    let language = subtags.next().unwrap_or_default();
+    let is_region = |subtag: &&str| match subtag.len() {
+        2 => subtag.bytes().all(|b| b.is_ascii_uppercase()),
+        3 => subtag.bytes().all(|b| b.is_ascii_digit()),
+        _ => false,
+    };
+    subtags
+        .take_while(|subtag| subtag.len() != 1)
+        .find(is_region)
+        .map(str::to_string)
+        .or_else(|| LIKELY_REGIONS.iter().find(|(candidate, _)| *candidate == language).map(|(_, region)| region.to_string()))
+}
+
+/// The measurement system and whether temperatures are in Fahrenheit
+pub(crate) fn system(tag: &str) -> (System, bool) {
+    let keyword = tag.split_once("-u-").and_then(|(_, keywords)| {
+        let keywords: Vec<&str> = keywords.split('-').collect();
+        keywords.windows(2).find(|pair| pair[0] == "ms").map(|pair| pair[1])
+    });
+    let region = region(tag).unwrap_or_default();
+    let system = match keyword {
+        Some("ussystem") => System::Us,
+        Some("uksystem") => System::Uk,
+        Some("metric") => System::Metric,
+        _ if US_SYSTEM.contains(&region.as_str()) => System::Us,
+        _ if UK_SYSTEM.contains(&region.as_str()) => System::Uk,
+        _ => System::Metric,
+    };
+    let fahrenheit = match keyword {
+        Some(_) => system == System::Us,
+        None => FAHRENHEIT.contains(&region.as_str()),
+    };
+    (system, fahrenheit)
+}
+
+/// The unit a measurement of a kind is shown in, for a base-unit value
+pub(crate) fn preferred(kind: &str, value: f64, system: System, fahrenheit: bool) -> Option<&'static str> {
+    let magnitude = value.abs();
+    Some(match (kind, system) {
+        ("distance", System::Us | System::Uk) => "mile",
+        ("distance", System::Metric) => "kilometer",
+        ("length", System::Us) if magnitude < 0.3048 => "inch",
+        ("length", System::Us) => "foot",
+        ("length", _) if magnitude < 1.0 => "centimeter",
+        ("length", _) => "meter",
+        ("mass", System::Us) => "pound",
+        ("mass", _) if magnitude < 1.0 => "gram",
+        ("mass", _) => "kilogram",
+        ("temperature", _) if fahrenheit => "fahrenheit",
+        ("temperature", _) => "celsius",
+        _ => return None,
+    })
+}
+
+/// Convert a value between two units of a kind; None for unknown units or
+/// different kinds
+pub(crate) fn convert(value: f64, from: &str, to: &str) -> Option<f64> {
+    let find = |unit: &str| UNITS.iter().find(|(name, ..)| *name == unit);
+    let &(_, from_kind, from_factor) = find(from)?;
+    let &(_, to_kind, to_factor) = find(to)?;
+    if from_kind != to_kind {
+        return None;
+    }
+    // Fahrenheit is offset from Celsius as well as scaled
+    let offset = |unit: &str| if unit == "fahrenheit" { 32.0 } else { 0.0 };
+    Some((value - offset(from)) * from_factor / to_factor + offset(to))
+}
@@ -0,0 +1,63 @@
+// gettext MO catalogs - read compiled .mo files into catalog entries
+//
+// Layout (either byte order, detected from the magic number):
+//   0  magic 0x950412de      4  revision
+//   8  string count N       12  offset of original-string table
+//  16  offset of translation table
+// Each table holds N (length, offset) pairs. A plural msgid is
+// "singular\0plural" and its translation "form0\0form1\0..."; a context is
+// "msgctxt\x04msgid", which is also the catalog key we keep.
+use super::{Catalog, PluralCategory, PluralForms};
+
+const MAGIC: u32 = 0x950412de;
+
+/// Parse MO bytes. The header entry (empty msgid) is ignored; strings must be UTF-8.
+pub(crate) fn parse(bytes: &[u8], categories: &[PluralCategory]) -> Result<Catalog, String> {
+    let big_endian = match bytes.get(..4) {
+        Some(magic) if u32::from_le_bytes(magic.try_into().unwrap()) == MAGIC => false,
+        Some(magic) if u32::from_be_bytes(magic.try_into().unwrap()) == MAGIC => true,
+        _ => return Err("not a gettext MO file".to_string()),
+    };
+    let read_u32 = |offset: usize| -> Result<usize, String> {
+        let word = bytes
+            .get(offset..offset + 4)
+            .ok_or_else(|| format!("truncated at byte {}", offset))?;
+        let word: [u8; 4] = word.try_into().unwrap();
+        Ok(if big_endian { u32::from_be_bytes(word) } else { u32::from_le_bytes(word) } as usize)
+    };
+    let read_string = |table: usize, index: usize| -> Result<&str, String> {
+        let length = read_u32(table + index * 8)?;
+        let offset = read_u32(table + index * 8 + 4)?;
+        let raw = offset
+            .checked_add(length)
+            .and_then(|end| bytes.get(offset..end))
+            .ok_or_else(|| format!("string {} out of bounds", index))?;
+        std::str::from_utf8(raw).map_err(|_| format!("string {} is not UTF-8", index))
+    };
+
+    let count = read_u32(8)?;
+    let originals = read_u32(12)?;
+    let translations = read_u32(16)?;
+
+    let mut catalog = Catalog::default();
+    for index in 0..count {
+        let original = read_string(originals, index)?;
+        let translation = read_string(translations, index)?;
+        if original.is_empty() {
+            continue;
+        }
+
+        match original.split_once('\0') {
+            // Plural entry: key on the singular msgid
+            Some((singular, _)) => {
+                let forms: Vec<String> = translation.split('\0').map(String::from).collect();
+                catalog.plurals.insert(singular.to_string(), PluralForms::from_indexed(categories, forms));
+            }
+            None => {
+                catalog.strings.insert(original.to_string(), translation.into());
+            }
+        }
+    }
+
+    Ok(catalog)
+}
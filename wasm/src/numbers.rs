@@ -0,0 +1,424 @@
+// Locale number formatting for interpolated counts and NumberFormat
+//
+// Decimal and grouping separators follow CLDR's latn number symbols for the
+// languages (and the few regional variants) listed below; any other locale
+// formats like English ("1,234.5"). Locales with minimumGroupingDigits 2
+// leave four-digit numbers ungrouped ("1234" but "12 345"), and Indian
+// grouping puts separators every two digits after the first three
+// ("12,34,567"). Digits follow the locale's numbering system: its CLDR
+// default (Arabic-Indic for ar-EG, Bengali for bn, Persian for fa) or a
+// -u-nu- extension ("ar-EG-u-nu-latn", "hi-u-nu-deva"). The Arabic systems
+// also bring their own separators ("١٬٢٣٤٫٥").
+use super::canonicalize_locale;
+
+#[derive(Clone, Copy)]
+pub(crate) struct NumberSymbols {
+    pub decimal: &'static str,
+    pub group: &'static str,
+    /// Integer digits below which no grouping separator is written, less 3
+    pub min_grouping: usize,
+    /// Size of every group but the last (3, or 2 for Indian grouping)
+    pub secondary: usize,
+    /// The numbering system's zero; the other digits follow it
+    pub zero: char,
+}
+
+impl NumberSymbols {
+    /// The latn symbols in another numbering system ("arab", "deva", ...),
+    /// or None for a system that is not supported
+    pub fn with_numbering_system(self, system: &str) -> Option<NumberSymbols> {
+        let &(_, zero) = NUMBERING_SYSTEMS.iter().find(|(name, _)| *name == system)?;
+        let (decimal, group) = match system {
+            "arab" | "arabext" => (ARABIC_DECIMAL, ARABIC_GROUP),
+            _ => (self.decimal, self.group),
+        };
+        Some(NumberSymbols { decimal, group, zero, ..self })
+    }
+}
+
+const ENGLISH: NumberSymbols = NumberSymbols {
+    decimal: ".",
+    group: ",",
+    min_grouping: 1,
+    secondary: 3,
+    zero: '0',
+};
+
+const NBSP: &str = "\u{a0}";
+const ARABIC_DECIMAL: &str = "\u{66b}";
+const ARABIC_GROUP: &str = "\u{66c}";
+const NARROW_NBSP: &str = "\u{202f}";
+
+/// (locale, decimal, group, minimum grouping digits, secondary group size)
+const SYMBOLS: &[(&str, &str, &str, usize, usize)] = &[
+    ("bg", ",", NBSP, 2, 3),
+    ("bn", ".", ",", 1, 2),
+    ("ca", ",", ".", 1, 3),
+    ("cs", ",", NBSP, 1, 3),
+    ("da", ",", ".", 1, 3),
+    ("de", ",", ".", 1, 3),
+    ("de-AT", ",", NBSP, 1, 3),
+    ("de-CH", ".", "’", 1, 3),
+    ("el", ",", ".", 1, 3),
+    ("en-IN", ".", ",", 1, 2),
+    ("es", ",", ".", 2, 3),
+    ("es-419", ".", ",", 1, 3),
+    ("es-MX", ".", ",", 1, 3),
+    ("es-US", ".", ",", 1, 3),
+    ("et", ",", NBSP, 2, 3),
+    ("fi", ",", NBSP, 1, 3),
+    ("fr", ",", NARROW_NBSP, 1, 3),
+    ("gu", ".", ",", 1, 2),
+    ("hi", ".", ",", 1, 2),
+    ("hr", ",", ".", 1, 3),
+    ("hu", ",", NBSP, 1, 3),
+    ("id", ",", ".", 1, 3),
+    ("it", ",", ".", 1, 3),
+    ("it-CH", ".", "’", 1, 3),
+    ("lt", ",", NBSP, 1, 3),
+    ("lv", ",", NBSP, 1, 3),
+    ("mr", ".", ",", 1, 2),
+    ("nb", ",", NBSP, 1, 3),
+    ("nl", ",", ".", 1, 3),
+    ("nn", ",", NBSP, 1, 3),
+    ("pl", ",", NBSP, 2, 3),
+    ("pt", ",", ".", 1, 3),
+    ("pt-PT", ",", NBSP, 2, 3),
+    ("ro", ",", ".", 1, 3),
+    ("ru", ",", NBSP, 1, 3),
+    ("sk", ",", NBSP, 1, 3),
+    ("sl", ",", ".", 1, 3),
+    ("sr", ",", ".", 1, 3),
+    ("sv", ",", NBSP, 1, 3),
+    ("ta", ".", ",", 1, 2),
+    ("tr", ",", ".", 1, 3),
+    ("uk", ",", NBSP, 1, 3),
+    ("vi", ",", ".", 1, 3),
+];
+
+/// Numbering systems by name, with their zero digit
+pub(crate) const NUMBERING_SYSTEMS: &[(&str, char)] = &[
+    ("latn", '0'),
+    ("arab", '\u{660}'),
+    ("arabext", '\u{6f0}'),
+    ("beng", '\u{9e6}'),
+    ("deva", '\u{966}'),
+    ("mymr", '\u{1040}'),
+    ("thai", '\u{e50}'),
+];
+
+/// Locales whose CLDR default numbering system is not latn, and the
+/// regions of those that use latn after all
+const DEFAULT_NUMBERING_SYSTEMS: &[(&str, &str)] = &[
+    ("ar", "arab"),
+    ("ar-AE", "latn"),
+    ("ar-DZ", "latn"),
+    ("ar-EH", "latn"),
+    ("ar-LY", "latn"),
+    ("ar-MA", "latn"),
+    ("ar-TN", "latn"),
+    ("bn", "beng"),
+    ("fa", "arabext"),
+    ("mr", "deva"),
+    ("my", "mymr"),
+    ("ne", "deva"),
+    ("ps", "arabext"),
+    ("ur-IN", "arabext"),
+];
+
+/// Symbols for a locale, trying "de-CH" before "de", in its numbering system
+pub(crate) fn symbols(locale: &str) -> NumberSymbols {
+    symbols_in(locale, None)
+}
+
+/// Symbols for a locale in a numbering system, or in the locale's own for
+/// None or one that is not supported
+pub(crate) fn symbols_in(locale: &str, system: Option<&str>) -> NumberSymbols {
+    let tag = canonicalize_locale(locale);
+    let symbols = lookup(&tag, SYMBOLS, |entry| entry.0).map_or(ENGLISH, |&(_, decimal, group, min_grouping, secondary)| {
+        NumberSymbols { decimal, group, min_grouping, secondary, zero: '0' }
+    });
+    let extension = tag.split_once("-u-").and_then(|(_, keywords)| {
+        let keywords: Vec<&str> = keywords.split('-').collect();
+        keywords.windows(2).find(|pair| pair[0] == "nu").map(|pair| pair[1])
+    });
+    let system = system
+        .or(extension)
+        .or_else(|| lookup(&tag, DEFAULT_NUMBERING_SYSTEMS, |entry| entry.0).map(|(_, system)| *system));
+    match system {
+        Some(system) => symbols.with_numbering_system(system).unwrap_or(symbols),
+        None => symbols,
+    }
+}
+
+/// The entry for the longest prefix of a tag ("de-CH", then "de"), ignoring
+/// extensions
+fn lookup<'t, T>(tag: &str, table: &'t [T], locale: impl Fn(&T) -> &str) -> Option<&'t T> {
+    let mut tag = tag.split("-u-").next().unwrap_or_default();
+    loop {
+        if let Some(entry) = table.iter().find(|entry| locale(entry) == tag) {
+            return Some(entry);
+        }
+        tag = &tag[..tag.rfind('-')?];
+    }
+}
+
+/// Format a plain decimal ("-1234.5") for a locale; anything else, such as
+/// compact "1.2c6", is returned unchanged
+pub(crate) fn format(decimal: &str, locale: &str) -> String {
+    format_with(decimal, &symbols(locale), true)
+}
+
+/// format with given symbols, with or without grouping separators
+pub(crate) fn format_with(decimal: &str, symbols: &NumberSymbols, grouping: bool) -> String {
+    let (sign, digits) = match decimal.strip_prefix('-') {
+        Some(digits) => ("-", digits),
+        None => ("", decimal),
+    };
+    let (integer, fraction) = match digits.split_once('.') {
+        Some((integer, fraction)) => (integer, Some(fraction)),
+        None => (digits, None),
+    };
+    let is_digits = |text: &str| !text.is_empty() && text.bytes().all(|b| b.is_ascii_digit());
+    if !is_digits(integer) || fraction.is_some_and(|fraction| !is_digits(fraction)) {
+        return decimal.to_string();
+    }
+
+    let mut groups = Vec::new();
+    let mut rest = integer;
+    if grouping && integer.len() >= 3 + symbols.min_grouping {
+        let (head, last) = rest.split_at(rest.len() - 3);
+        groups.push(last);
+        rest = head;
+        while rest.len() > symbols.secondary {
+            let (head, group) = rest.split_at(rest.len() - symbols.secondary);
+            groups.push(group);
+            rest = head;
+        }
+    }
+    groups.push(rest);
+    groups.reverse();
+
+    let mut out = format!("{}{}", sign, groups.join(symbols.group));
+    if let Some(fraction) = fraction {
+        out.push_str(symbols.decimal);
+        out.push_str(fraction);
+    }
+    match symbols.zero {
+        '0' => out,
+        zero => out
+            .chars()
+            .map(|c| match c.to_digit(10) {
+                Some(digit) if c.is_ascii_digit() => char::from_u32(zero as u32 + digit).unwrap_or(c),
+                _ => c,
+            })
+            .collect(),
+    }
+}
+
+/// Currency symbols parse skips next to a number
+const CURRENCY_SIGNS: &[char] = &['$', '€', '£', '¥', '₹', '₩', '₽', '¢', '₺', '₪', '₫', '฿', '₴', '₦', '₱', '₣'];
+
+/// Read a number written for a locale: "1.234,56" in de, "1 234,56" in fr,
+/// "١٬٢٣٤٫٥٦" in ar. Digits of any supported numbering system are read;
+/// grouping separators and whitespace are skipped (an apostrophe stands in
+/// for de-CH's "’"); a leading or trailing "-", "−" or "+", parentheses
+/// for a negative amount, "%" or "‰", and a currency symbol or ISO code
+/// around the number are understood. None when anything else is left.
+pub(crate) fn parse(text: &str, locale: &str) -> Option<f64> {
+    let symbols = symbols(locale);
+    let mut rest: String = text
+        .chars()
+        .filter(|c| !c.is_whitespace() && !matches!(c, '\u{200e}' | '\u{200f}' | '\u{61c}'))
+        .map(|c| {
+            NUMBERING_SYSTEMS
+                .iter()
+                .find_map(|&(_, zero)| (c as u32).checked_sub(zero as u32).filter(|digit| *digit < 10))
+                .and_then(|digit| char::from_digit(digit, 10))
+                .unwrap_or(c)
+        })
+        .collect();
+
+    let mut negative = false;
+    let mut scale = 1.0;
+    loop {
+        let before = rest.len();
+        if let Some(inner) = rest.strip_prefix('(').and_then(|inner| inner.strip_suffix(')')) {
+            rest = inner.to_string();
+            negative = true;
+        }
+        for sign in ['-', '\u{2212}'] {
+            if let Some(inner) = rest.strip_prefix(sign).or_else(|| rest.strip_suffix(sign)) {
+                rest = inner.to_string();
+                negative = true;
+            }
+        }
+        if let Some(inner) = rest.strip_prefix('+').or_else(|| rest.strip_suffix('+')) {
+            rest = inner.to_string();
+        }
+        for (sign, divisor) in [('%', 100.0), ('\u{66a}', 100.0), ('‰', 1000.0)] {
+            if let Some(inner) = rest.strip_prefix(sign).or_else(|| rest.strip_suffix(sign)) {
+                rest = inner.to_string();
+                scale /= divisor;
+            }
+        }
+        rest = rest.trim_matches(CURRENCY_SIGNS).to_string();
+        let code = |part: Option<&str>| part.is_some_and(|part| part.bytes().all(|b| b.is_ascii_uppercase()));
+        if rest.len() > 3 && code(rest.get(..3)) {
+            rest.drain(..3);
+        } else if rest.len() > 3 && code(rest.get(rest.len() - 3..)) {
+            rest.truncate(rest.len() - 3);
+        }
+        if rest.len() == before {
+            break;
+        }
+    }
+
+    let groups: &[&str] = match symbols.group {
+        "’" => &["’", "'"],
+        group if group.trim().is_empty() => &[],
+        group => &[group, ARABIC_GROUP],
+    };
+    let mut plain = String::with_capacity(rest.len());
+    let mut chars = rest.as_str();
+    while let Some(c) = chars.chars().next() {
+        if let Some(decimal) = [symbols.decimal, ARABIC_DECIMAL].into_iter().find(|decimal| chars.starts_with(decimal)) {
+            if plain.contains('.') {
+                return None;
+            }
+            plain.push('.');
+            chars = &chars[decimal.len()..];
+        } else if let Some(group) = groups.iter().find(|group| chars.starts_with(*group)) {
+            chars = &chars[group.len()..];
+        } else if c.is_ascii_digit() {
+            plain.push(c);
+            chars = &chars[1..];
+        } else {
+            return None;
+        }
+    }
+    if !plain.bytes().any(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let number = plain.parse::<f64>().ok()? * scale;
+    Some(if negative { -number } else { number })
+}
+
+/// How to round a number's magnitude; NumberFormat maps the signed modes
+/// (ceil, floor, ...) onto these by the sign
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Rounding {
+    /// Half away from zero
+    HalfExpand,
+    /// Half toward zero
+    HalfTrunc,
+    /// Half to the even neighbour
+    HalfEven,
+    /// Away from zero
+    Expand,
+    /// Toward zero
+    Trunc,
+}
+
+/// A number's magnitude as a plain decimal with between min and max
+/// fraction digits, rounded to a multiple of increment in the last one (5
+/// with two fraction digits is the nearest 0.05). Rounding works on the
+/// shortest decimal form, so ties are exact: 1.005 rounds half away from
+/// zero to "1.01", as Intl.NumberFormat does.
+pub(crate) fn fixed(number: f64, min_fraction: usize, max_fraction: usize, increment: u32, rounding: Rounding) -> String {
+    let shortest = number.abs().to_string();
+    let (integer, fraction) = shortest.split_once('.').unwrap_or((&shortest, ""));
+    let (kept, dropped) = fraction.split_at(fraction.len().min(max_fraction));
+    // The number in units of the last kept digit, less the dropped digits
+    let units = format!("{}{}{}", integer, kept, "0".repeat(max_fraction - kept.len()));
+
+    // Long division by the increment
+    let increment = u64::from(increment.max(1));
+    let mut quotient = String::with_capacity(units.len());
+    let mut remainder = 0u64;
+    for digit in units.bytes() {
+        let value = remainder * 10 + u64::from(digit - b'0');
+        quotient.push(char::from(b'0' + (value / increment) as u8));
+        remainder = value % increment;
+    }
+
+    // Where the discarded part falls against half an increment
+    let dropped_zero = dropped.bytes().all(|b| b == b'0');
+    let exact = remainder == 0 && dropped_zero;
+    let half = match (2 * remainder).cmp(&increment) {
+        std::cmp::Ordering::Greater => std::cmp::Ordering::Greater,
+        std::cmp::Ordering::Equal if dropped_zero => std::cmp::Ordering::Equal,
+        std::cmp::Ordering::Equal => std::cmp::Ordering::Greater,
+        std::cmp::Ordering::Less if 2 * remainder + 1 == increment => {
+            let tail = dropped.trim_end_matches('0');
+            match tail {
+                "5" => std::cmp::Ordering::Equal,
+                _ => tail.cmp("5"),
+            }
+        }
+        std::cmp::Ordering::Less => std::cmp::Ordering::Less,
+    };
+    let odd = quotient.bytes().last().is_some_and(|digit| (digit - b'0') % 2 == 1);
+    let round_up = match rounding {
+        Rounding::Trunc => false,
+        Rounding::Expand => !exact,
+        Rounding::HalfExpand => half.is_ge(),
+        Rounding::HalfTrunc => half.is_gt(),
+        Rounding::HalfEven => half.is_gt() || (half.is_eq() && odd),
+    };
+    if round_up {
+        quotient = add_one(&quotient);
+    }
+
+    let digits = multiply(&quotient, increment);
+    let digits = format!("{}{}", "0".repeat((max_fraction + 1).saturating_sub(digits.len())), digits);
+    let (integer, fraction) = digits.split_at(digits.len() - max_fraction);
+    let mut out = match integer.trim_start_matches('0') {
+        "" => "0".to_string(),
+        integer => integer.to_string(),
+    };
+    let mut fraction = fraction.to_string();
+    while fraction.len() > min_fraction && fraction.ends_with('0') {
+        fraction.pop();
+    }
+    if !fraction.is_empty() {
+        out.push('.');
+        out.push_str(&fraction);
+    }
+    out
+}
+
+/// A decimal integer plus one
+fn add_one(digits: &str) -> String {
+    let mut digits = digits.as_bytes().to_vec();
+    for digit in digits.iter_mut().rev() {
+        if *digit == b'9' {
+            *digit = b'0';
+        } else {
+            *digit += 1;
+            return String::from_utf8(digits).unwrap_or_default();
+        }
+    }
+    digits.insert(0, b'1');
+    String::from_utf8(digits).unwrap_or_default()
+}
+
+/// A decimal integer times a small factor
+fn multiply(digits: &str, factor: u64) -> String {
+    if factor == 1 {
+        return digits.to_string();
+    }
+    let mut out = Vec::with_capacity(digits.len() + 4);
+    let mut carry = 0u64;
+    for digit in digits.bytes().rev() {
+        let value = u64::from(digit - b'0') * factor + carry;
+        out.push(b'0' + (value % 10) as u8);
+        carry = value / 10;
+    }
+    while carry > 0 {
+        out.push(b'0' + (carry % 10) as u8);
+        carry /= 10;
+    }
+    out.reverse();
+    String::from_utf8(out).unwrap_or_default()
+}
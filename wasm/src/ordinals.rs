@@ -0,0 +1,52 @@
+// Ordinal suffixes for formatOrdinal
+//
+// The rank's CLDR ordinal category picks the form ("1st", "2nd", "3rd",
+// "4th" in English; "1er", "2e" in French), following CLDR's
+// digits-ordinal rule sets; languages that write ordinals with a full stop
+// ("1." in German) have only "other". "{0}" is the number written for the
+// locale. Languages not listed get the number alone.
+
+/// (language, forms by ordinal category)
+const ORDINALS: &[(&str, &[(&str, &str)])] = &[
+    ("ca", &[("one", "{0}r"), ("two", "{0}n"), ("few", "{0}t"), ("other", "{0}è")]),
+    ("cs", &[("other", "{0}.")]),
+    ("da", &[("other", "{0}.")]),
+    ("de", &[("other", "{0}.")]),
+    ("en", &[("one", "{0}st"), ("two", "{0}nd"), ("few", "{0}rd"), ("other", "{0}th")]),
+    ("es", &[("other", "{0}.º")]),
+    ("et", &[("other", "{0}.")]),
+    ("fi", &[("other", "{0}.")]),
+    ("fr", &[("one", "{0}er"), ("other", "{0}e")]),
+    ("hr", &[("other", "{0}.")]),
+    ("hu", &[("other", "{0}.")]),
+    ("is", &[("other", "{0}.")]),
+    ("it", &[("other", "{0}º")]),
+    ("ja", &[("other", "第{0}")]),
+    ("ko", &[("other", "{0}번째")]),
+    ("lv", &[("other", "{0}.")]),
+    ("nb", &[("other", "{0}.")]),
+    ("nl", &[("other", "{0}e")]),
+    ("nn", &[("other", "{0}.")]),
+    ("pl", &[("other", "{0}.")]),
+    ("pt", &[("other", "{0}º")]),
+    ("ru", &[("other", "{0}-й")]),
+    ("sk", &[("other", "{0}.")]),
+    ("sl", &[("other", "{0}.")]),
+    ("sr", &[("other", "{0}.")]),
+    ("sv", &[("one", "{0}:a"), ("other", "{0}:e")]),
+    ("tr", &[("other", "{0}.")]),
+    ("uk", &[("other", "{0}-й")]),
+    ("zh", &[("other", "第{0}")]),
+];
+
+/// The ordinal form of a formatted number for a language and category
+pub(crate) fn apply(language: &str, category: &str, number: &str) -> String {
+    let Some((_, forms)) = ORDINALS.iter().find(|(candidate, _)| *candidate == language) else {
+        return number.to_string();
+    };
+    forms
+        .iter()
+        .find(|(candidate, _)| *candidate == category)
+        .or_else(|| forms.iter().find(|(candidate, _)| *candidate == "other"))
+        .map_or_else(|| number.to_string(), |(_, form)| form.replace("{0}", number))
+}
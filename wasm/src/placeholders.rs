@@ -0,0 +1,117 @@
+// Placeholder extraction for catalog validation
+//
+// Recognizes mustache/i18next {{name}}, printf %s / %2$d (sequential ones are
+// numbered by position, so "%s %d" and "%1$s %2$d" agree) and ICU {name} /
+// {name, number} / {count, plural, ...} arguments, including arguments nested
+// in plural and select cases. Each placeholder has an identity (syntax and
+// name) and a type: the printf conversion or the ICU argument type.
+use std::collections::BTreeMap;
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Placeholder {
+    /// Type: printf conversion ("d", "s", ...), ICU argument type ("number",
+    /// "plural", ...) or empty for untyped {name} / {{name}}
+    pub kind: String,
+    /// As written in the message
+    pub text: String,
+}
+
+/// Placeholders of a message keyed by identity: "{{name}}", "%1", "{name}"
+pub(crate) fn extract(message: &str) -> BTreeMap<String, Placeholder> {
+    let mut found = BTreeMap::new();
+    let mut position = 0;
+    let mut rest = message;
+
+    while let Some(start) = rest.find(['{', '%']) {
+        let tail = &rest[start..];
+        // "other{{name} files}" is an ICU case holding {name}, not {{name}}
+        let mustache = tail
+            .strip_prefix("{{")
+            .and_then(|inner| Some((inner, inner.find("}}")?)))
+            .filter(|(inner, end)| !inner[..*end].contains(['{', '}']));
+        let consumed = if let Some((inner, end)) = mustache {
+            let name = inner[..end].trim().trim_start_matches('-').split(',').next().unwrap_or("").trim();
+            if !name.is_empty() {
+                let placeholder = Placeholder {
+                    kind: String::new(),
+                    text: tail[..end + 4].to_string(),
+                };
+                found.entry(format!("{{{{{}}}}}", name)).or_insert(placeholder);
+            }
+            end + 4
+        } else if tail.starts_with('{') {
+            if let Some((name, kind, text)) = icu_argument(tail) {
+                let placeholder = Placeholder { kind, text };
+                found.entry(format!("{{{}}}", name)).or_insert(placeholder);
+            }
+            // Step inside so arguments nested in plural/select cases are seen
+            1
+        } else if tail.starts_with("%%") {
+            2
+        } else {
+            match printf_conversion(tail) {
+                Some((index, kind, length)) => {
+                    let index = index.unwrap_or_else(|| {
+                        position += 1;
+                        position
+                    });
+                    let placeholder = Placeholder {
+                        kind,
+                        text: tail[..length].to_string(),
+                    };
+                    found.entry(format!("%{}", index)).or_insert(placeholder);
+                    length
+                }
+                None => 1,
+            }
+        };
+        rest = &tail[consumed..];
+    }
+    found
+}
+
+/// `{name}` or `{name, type, ...}` at the start of text: (name, type, display text)
+fn icu_argument(text: &str) -> Option<(String, String, String)> {
+    let inner = &text[1..];
+    let end = inner.find(['}', ','])?;
+    let name = inner[..end].trim();
+    if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+        return None;
+    }
+    if inner[end..].starts_with('}') {
+        return Some((name.to_string(), String::new(), text[..end + 2].to_string()));
+    }
+    let after = &inner[end + 1..];
+    let kind_end = after.find([',', '}'])?;
+    let kind = after[..kind_end].trim().to_string();
+    let shown = format!("{{{}, {}}}", name, kind);
+    Some((name.to_string(), kind, shown))
+}
+
+/// A printf conversion at the start of text: (explicit position, conversion, length)
+pub(crate) fn printf_conversion(text: &str) -> Option<(Option<usize>, String, usize)> {
+    let bytes = text.as_bytes();
+    let mut index = 1;
+    let digits = |from: usize| bytes[from..].iter().take_while(|b| b.is_ascii_digit()).count();
+
+    let mut position = None;
+    let leading = digits(index);
+    if leading > 0 && bytes.get(index + leading) == Some(&b'$') {
+        position = text[index..index + leading].parse().ok();
+        index += leading + 1;
+    }
+    // No space flag: "50% off" is text, not a "% o" conversion
+    index += bytes[index..].iter().take_while(|b| b"-+0#'".contains(b)).count();
+    index += if bytes.get(index) == Some(&b'*') { 1 } else { digits(index) };
+    if bytes.get(index) == Some(&b'.') {
+        index += 1;
+        index += digits(index);
+    }
+    index += bytes[index..].iter().take_while(|b| b"hlLqjzt".contains(b)).count();
+
+    let conversion = *bytes.get(index)?;
+    if !b"diouxXeEfFgGaAcspn@".contains(&conversion) {
+        return None;
+    }
+    Some((position, (conversion as char).to_string(), index + 1))
+}
@@ -0,0 +1,285 @@
+// gettext PO catalogs - parse .po text into catalog entries and write them back
+//
+// Plural msgstr[N] entries map onto the locale's CLDR categories in CLDR
+// order (one, few, many, other for ru), which is also the order exported
+// Plural-Forms expressions produce.
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+
+use i18n_core::{Operand, Relation, RuleSet};
+
+use super::{Catalog, PluralCategory, PluralForms};
+
+/// Joins msgctxt and msgid into one catalog key, as gettext does internally
+pub(crate) const CONTEXT_SEPARATOR: char = '\u{4}';
+
+/// One entry being read: msgctxt, msgid, msgid_plural and msgstr[N]
+#[derive(Default)]
+struct Entry {
+    context: Option<String>,
+    id: Option<String>,
+    id_plural: Option<String>,
+    translations: BTreeMap<usize, String>,
+    fuzzy: bool,
+}
+
+/// Field that continuation lines ("...") append to
+#[derive(Clone, Copy)]
+enum Field {
+    Context,
+    Id,
+    IdPlural,
+    Translation(usize),
+}
+
+/// Parse PO text. Fuzzy, obsolete and untranslated entries are skipped,
+/// as gettext does at runtime; the header entry (msgid "") is ignored.
+pub(crate) fn parse(text: &str, categories: &[PluralCategory]) -> Result<Catalog, String> {
+    let mut catalog = Catalog::default();
+    let mut entry = Entry::default();
+    let mut field: Option<Field> = None;
+
+    for (index, raw) in text.lines().enumerate() {
+        let line = raw.trim();
+        let error = |message: &str| format!("line {}: {}", index + 1, message);
+
+        if line.is_empty() || line.starts_with("#~") {
+            continue;
+        }
+        if let Some(comment) = line.strip_prefix('#') {
+            // A comment after msgstr starts the next entry
+            if entry.id.is_some() && !entry.translations.is_empty() {
+                finish_entry(std::mem::take(&mut entry), categories, &mut catalog);
+                field = None;
+            }
+            if let Some(flags) = comment.strip_prefix(',') {
+                entry.fuzzy |= flags.split(',').any(|flag| flag.trim() == "fuzzy");
+            }
+            continue;
+        }
+        if line.starts_with('"') {
+            let value = unquote(line).ok_or_else(|| error("invalid string"))?;
+            let target = match field.ok_or_else(|| error("string outside an entry"))? {
+                Field::Context => entry.context.get_or_insert_with(String::new),
+                Field::Id => entry.id.get_or_insert_with(String::new),
+                Field::IdPlural => entry.id_plural.get_or_insert_with(String::new),
+                Field::Translation(n) => entry.translations.entry(n).or_default(),
+            };
+            target.push_str(&value);
+            continue;
+        }
+
+        let (keyword, rest) = line.split_once(char::is_whitespace).ok_or_else(|| error("expected a keyword"))?;
+        let value = unquote(rest.trim()).ok_or_else(|| error("invalid string"))?;
+
+        // msgctxt or msgid after a msgstr starts the next entry
+        if matches!(keyword, "msgctxt" | "msgid") && !entry.translations.is_empty() {
+            finish_entry(std::mem::take(&mut entry), categories, &mut catalog);
+        }
+
+        field = Some(match keyword {
+            "msgctxt" => {
+                entry.context = Some(value);
+                Field::Context
+            }
+            "msgid" => {
+                entry.id = Some(value);
+                Field::Id
+            }
+            "msgid_plural" => {
+                entry.id_plural = Some(value);
+                Field::IdPlural
+            }
+            "msgstr" => {
+                entry.translations.insert(0, value);
+                Field::Translation(0)
+            }
+            _ => {
+                let n = keyword
+                    .strip_prefix("msgstr[")
+                    .and_then(|rest| rest.strip_suffix(']'))
+                    .and_then(|n| n.parse::<usize>().ok())
+                    .ok_or_else(|| error(&format!("unknown keyword {}", keyword)))?;
+                entry.translations.insert(n, value);
+                Field::Translation(n)
+            }
+        });
+    }
+
+    finish_entry(entry, categories, &mut catalog);
+    Ok(catalog)
+}
+
+fn finish_entry(entry: Entry, categories: &[PluralCategory], catalog: &mut Catalog) {
+    let Some(id) = entry.id else { return };
+    if entry.fuzzy || (id.is_empty() && entry.context.is_none()) {
+        return;
+    }
+
+    let key = match entry.context {
+        Some(context) => format!("{}{}{}", context, CONTEXT_SEPARATOR, id),
+        None => id,
+    };
+
+    if entry.id_plural.is_none() {
+        if let Some(translation) = entry.translations.get(&0).filter(|t| !t.is_empty()) {
+            catalog.strings.insert(key, translation.as_str().into());
+        }
+        return;
+    }
+
+    let translations: Vec<String> = entry.translations.into_values().collect();
+    if translations.iter().all(|t| t.is_empty()) {
+        return;
+    }
+    catalog.plurals.insert(key, PluralForms::from_indexed(categories, translations));
+}
+
+/// Write a catalog as PO text, entries sorted by key
+pub(crate) fn write(catalog: &Catalog, locale: &str, rules: RuleSet, categories: &[PluralCategory]) -> String {
+    let mut out = String::new();
+    out.push_str("msgid \"\"\nmsgstr \"\"\n");
+    for header in [
+        format!("Language: {}", locale),
+        "MIME-Version: 1.0".to_string(),
+        "Content-Type: text/plain; charset=UTF-8".to_string(),
+        "Content-Transfer-Encoding: 8bit".to_string(),
+        format!("Plural-Forms: nplurals={}; plural={};", categories.len(), plural_expression(rules)),
+    ] {
+        writeln!(out, "\"{}\\n\"", escape(&header)).unwrap();
+    }
+
+    let strings: BTreeMap<&String, &str> = catalog.strings.iter().map(|(key, text)| (key, &**text)).collect();
+    for (key, translation) in strings {
+        out.push('\n');
+        write_id(&mut out, key);
+        writeln!(out, "msgstr \"{}\"", escape(translation)).unwrap();
+    }
+
+    let plurals: BTreeMap<&String, &PluralForms> = catalog.plurals.iter().collect();
+    for (key, forms) in plurals {
+        out.push('\n');
+        let id = write_id(&mut out, key);
+        writeln!(out, "msgid_plural \"{}\"", escape(id)).unwrap();
+        for (n, category) in categories.iter().enumerate() {
+            let translation = forms.get(*category).unwrap_or(&forms.other);
+            writeln!(out, "msgstr[{}] \"{}\"", n, escape(translation)).unwrap();
+        }
+    }
+
+    out
+}
+
+/// Write msgctxt (if the key carries one) and msgid; returns the msgid
+fn write_id<'a>(out: &mut String, key: &'a str) -> &'a str {
+    let id = match key.split_once(CONTEXT_SEPARATOR) {
+        Some((context, id)) => {
+            writeln!(out, "msgctxt \"{}\"", escape(context)).unwrap();
+            id
+        }
+        None => key,
+    };
+    writeln!(out, "msgid \"{}\"", escape(id)).unwrap();
+    id
+}
+
+/// gettext plural expression for integer n, selecting the index of the
+/// matching category in CLDR order (the last index is "other")
+fn plural_expression(rules: RuleSet) -> String {
+    let mut expression = String::new();
+    for (index, (_, condition)) in rules.iter().enumerate() {
+        let or_chain: Vec<String> = condition
+            .iter()
+            .map(|and_chain| {
+                // Drop relations that are constant for integers ("v = 0")
+                let relations: Vec<String> = and_chain
+                    .iter()
+                    .map(relation_expression)
+                    .filter(|relation| relation != "1")
+                    .collect();
+                if relations.iter().any(|relation| relation == "0") {
+                    "0".to_string()
+                } else if relations.is_empty() {
+                    "1".to_string()
+                } else {
+                    relations.join(" && ")
+                }
+            })
+            .collect();
+        write!(expression, "({}) ? {} : ", or_chain.join(" || "), index).unwrap();
+    }
+    write!(expression, "{}", rules.len()).unwrap();
+    if rules.is_empty() {
+        expression
+    } else {
+        format!("({})", expression)
+    }
+}
+
+fn relation_expression(relation: &Relation) -> String {
+    // gettext counts are integers: every fraction operand and e are 0
+    let operand = match relation.operand {
+        Operand::N | Operand::I => "n",
+        _ => {
+            let zero_matches = relation.ranges.iter().any(|&(low, _)| low == 0);
+            return if zero_matches != relation.negated { "1" } else { "0" }.to_string();
+        }
+    };
+
+    let value = match relation.modulus {
+        0 => operand.to_string(),
+        modulus => format!("n % {}", modulus),
+    };
+    if relation.negated {
+        let ranges: Vec<String> = relation
+            .ranges
+            .iter()
+            .map(|&(low, high)| match low == high {
+                true => format!("{} != {}", value, low),
+                false => format!("({} < {} || {} > {})", value, low, value, high),
+            })
+            .collect();
+        return ranges.join(" && ");
+    }
+
+    let ranges: Vec<String> = relation
+        .ranges
+        .iter()
+        .map(|&(low, high)| match low == high {
+            true => format!("{} == {}", value, low),
+            false => format!("{} >= {} && {} <= {}", value, low, value, high),
+        })
+        .collect();
+    match ranges.len() {
+        1 => ranges[0].clone(),
+        _ => format!("({})", ranges.join(" || ")),
+    }
+}
+
+/// Decode a quoted PO string
+fn unquote(text: &str) -> Option<String> {
+    let inner = text.strip_prefix('"')?.strip_suffix('"')?;
+    let mut result = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        match chars.next()? {
+            'n' => result.push('\n'),
+            't' => result.push('\t'),
+            'r' => result.push('\r'),
+            other => result.push(other),
+        }
+    }
+    Some(result)
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+        .replace('\t', "\\t")
+        .replace('\r', "\\r")
+}
@@ -0,0 +1,122 @@
+// Java .properties message bundles
+//
+// Follows java.util.Properties: "key=value", "key: value" or "key value";
+// # and ! comment lines; a line ending in an odd number of backslashes
+// continues on the next line (leading whitespace dropped); \t \n \r \f and
+// \uXXXX escapes (surrogate pairs combined), any other escaped character
+// stands for itself. Bytes are read as UTF-8 and, like PropertyResourceBundle
+// since Java 9, fall back to ISO-8859-1 when they are not valid UTF-8.
+use super::Catalog;
+
+/// Separator whitespace: space, tab and form feed
+const WHITESPACE: [char; 3] = [' ', '\t', '\u{c}'];
+
+/// Decode bundle bytes: UTF-8 if valid, otherwise ISO-8859-1
+pub(crate) fn decode(bytes: &[u8]) -> String {
+    match std::str::from_utf8(bytes) {
+        Ok(text) => text.to_string(),
+        Err(_) => bytes.iter().map(|&byte| byte as char).collect(),
+    }
+}
+
+pub(crate) fn parse(text: &str) -> Result<Catalog, String> {
+    let mut catalog = Catalog::default();
+    let mut lines = text.strip_prefix('\u{feff}').unwrap_or(text).lines().enumerate();
+
+    while let Some((number, line)) = lines.next() {
+        let line = line.trim_start_matches(WHITESPACE);
+        if line.is_empty() || line.starts_with('#') || line.starts_with('!') {
+            continue;
+        }
+
+        let mut logical = line.to_string();
+        while ends_with_continuation(&logical) {
+            logical.pop();
+            match lines.next() {
+                Some((_, next)) => logical.push_str(next.trim_start_matches(WHITESPACE)),
+                None => break,
+            }
+        }
+
+        let (key, value) = split_entry(&logical);
+        let key = unescape(key).map_err(|e| format!("line {}: {}", number + 1, e))?;
+        let value = unescape(value).map_err(|e| format!("line {}: {}", number + 1, e))?;
+        catalog.strings.insert(key, value.into());
+    }
+    Ok(catalog)
+}
+
+fn ends_with_continuation(line: &str) -> bool {
+    line.chars().rev().take_while(|&c| c == '\\').count() % 2 == 1
+}
+
+/// Split at the first unescaped separator ('=', ':' or whitespace)
+fn split_entry(line: &str) -> (&str, &str) {
+    let mut escaped = false;
+    for (index, c) in line.char_indices() {
+        if escaped {
+            escaped = false;
+            continue;
+        }
+        match c {
+            '\\' => escaped = true,
+            '=' | ':' | ' ' | '\t' | '\u{c}' => {
+                // Whitespace may be followed by an '=' or ':' of its own
+                let mut value = line[index + 1..].trim_start_matches(WHITESPACE);
+                if c != '=' && c != ':' {
+                    value = value.strip_prefix(['=', ':']).unwrap_or(value).trim_start_matches(WHITESPACE);
+                }
+                return (&line[..index], value);
+            }
+            _ => {}
+        }
+    }
+    (line, "")
+}
+
+fn unescape(text: &str) -> Result<String, String> {
+    if !text.contains('\\') {
+        return Ok(text.to_string());
+    }
+
+    let mut result = String::with_capacity(text.len());
+    let mut chars = text.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('t') => result.push('\t'),
+            Some('n') => result.push('\n'),
+            Some('r') => result.push('\r'),
+            Some('f') => result.push('\u{c}'),
+            Some('u') => {
+                let mut code = utf16_unit(&mut chars)?;
+                if (0xd800..=0xdbff).contains(&code) && chars.as_str().starts_with("\\u") {
+                    let mut lookahead = chars.clone();
+                    lookahead.nth(1);
+                    let low = utf16_unit(&mut lookahead)?;
+                    if (0xdc00..=0xdfff).contains(&low) {
+                        code = 0x10000 + ((code - 0xd800) << 10) + (low - 0xdc00);
+                        chars = lookahead;
+                    }
+                }
+                result.push(char::from_u32(code).ok_or_else(|| format!("unpaired surrogate \\u{:04x}", code))?);
+            }
+            Some(other) => result.push(other),
+            None => {}
+        }
+    }
+    Ok(result)
+}
+
+/// The four hex digits of a \uXXXX escape
+fn utf16_unit(chars: &mut std::str::Chars) -> Result<u32, String> {
+    let hex: String = chars.by_ref().take(4).collect();
+    u32::from_str_radix(&hex, 16)
+        .ok()
+        .filter(|_| hex.len() == 4)
+        .ok_or_else(|| format!("invalid \\u{} escape", hex))
+}
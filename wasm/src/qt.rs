@@ -0,0 +1,76 @@
+// Qt Linguist translation sources - .ts XML files
+//
+// Each <context> becomes a catalog named after the context, keyed on the
+// <source> text (or the message id="" used by qsTrId). A disambiguation
+// <comment> joins the key like a gettext msgctxt: "comment\x04source".
+// numerus="yes" messages become plural blocks from their <numerusform>s in
+// the locale's category order, with Qt's %n rewritten to the %d count
+// placeholder. Unfinished, vanished and obsolete translations are skipped,
+// as lrelease does by default.
+use super::po::CONTEXT_SEPARATOR;
+use super::xml::{self, Element};
+use super::{Catalog, PluralCategory, PluralForms};
+
+/// Parse a .ts document into (context name, catalog) pairs
+pub(crate) fn parse(text: &str, categories: &[PluralCategory]) -> Result<Vec<(String, Catalog)>, String> {
+    let root = xml::parse(text)?;
+    if root.name != "TS" {
+        return Err(format!("expected <TS>, found <{}>", root.name));
+    }
+
+    let mut contexts: Vec<(String, Catalog)> = Vec::new();
+    for context in root.elements().filter(|element| element.name == "context") {
+        let name = context
+            .child("name")
+            .map(Element::text)
+            .ok_or("<context> without a <name>")?;
+        let mut catalog = Catalog::default();
+        for message in context.elements().filter(|element| element.name == "message") {
+            add_message(&mut catalog, message, categories)?;
+        }
+
+        // A context may be split across several <context> elements
+        match contexts.iter_mut().find(|(existing, _)| *existing == name) {
+            Some((_, existing)) => {
+                existing.strings.extend(catalog.strings);
+                existing.plurals.extend(catalog.plurals);
+                existing.metadata.extend(catalog.metadata);
+            }
+            None => contexts.push((name, catalog)),
+        }
+    }
+    Ok(contexts)
+}
+
+fn add_message(catalog: &mut Catalog, message: &Element, categories: &[PluralCategory]) -> Result<(), String> {
+    let translation = message.child("translation").ok_or("<message> without a <translation>")?;
+    if matches!(translation.attribute("type"), Some("unfinished" | "vanished" | "obsolete")) {
+        return Ok(());
+    }
+
+    let source = match message.attribute("id") {
+        Some(id) => id.to_string(),
+        None => message.child("source").map(Element::text).ok_or("<message> without a <source>")?,
+    };
+    let key = match message.child("comment").map(Element::text) {
+        Some(comment) if !comment.is_empty() => format!("{}{}{}", comment, CONTEXT_SEPARATOR, source),
+        _ => source,
+    };
+
+    if message.attribute("numerus") == Some("yes") {
+        let forms: Vec<String> = translation
+            .elements()
+            .filter(|element| element.name == "numerusform")
+            .map(|form| form.text().replace("%n", "%d"))
+            .collect();
+        if forms.iter().any(|form| !form.is_empty()) {
+            catalog.plurals.insert(key, PluralForms::from_indexed(categories, forms));
+        }
+    } else {
+        let text = translation.text();
+        if !text.is_empty() {
+            catalog.strings.insert(key, text.into());
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,34 @@
+// .NET ResX resource files
+//
+// Each <data name="..."><value>..</value></data> string resource loads as a
+// catalog string, its text taken verbatim (composite-format placeholders such
+// as {0} stay as written). Non-string resources - entries with a type or
+// mimetype attribute, e.g. embedded images or ResXFileRef links - and the
+// <resheader>/<assembly>/<metadata> elements are skipped. A <comment> is kept
+// as the key's metadata comment when requested.
+use super::xml;
+use super::Catalog;
+
+pub(crate) fn parse(text: &str, keep_comments: bool) -> Result<Catalog, String> {
+    let root = xml::parse(text)?;
+    if root.name != "root" {
+        return Err(format!("expected <root>, found <{}>", root.name));
+    }
+
+    let mut catalog = Catalog::default();
+    for data in root.elements().filter(|element| element.name == "data") {
+        if data.attribute("type").is_some() || data.attribute("mimetype").is_some() {
+            continue;
+        }
+        let name = data.attribute("name").ok_or("<data> without a name")?;
+        let value = data.child("value").map(|value| value.text()).unwrap_or_default();
+        catalog.strings.insert(name.to_string(), value.into());
+
+        if keep_comments {
+            if let Some(comment) = data.child("comment").map(|comment| comment.text()) {
+                catalog.metadata.entry(name.to_string()).or_default().comment = Some(comment);
+            }
+        }
+    }
+    Ok(catalog)
+}
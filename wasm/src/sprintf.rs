@@ -0,0 +1,302 @@
+// printf-style formatting for formatSprintf
+//
+// Conversions follow C's printf: %[position$][flags][width][.precision]type
+// with the flags "-" (left-justify), "0" (zero-pad), "+" (always a sign)
+// and "#" (0x / 0 prefixes, keep a trailing point), a width or precision of
+// "*" read from the next argument, and the types d i u x X o f F e E g G s c.
+// "%%" writes a percent sign; length modifiers (l, ll, z, ...) are accepted
+// and ignored. There is no space flag, so "50% off" stays text.
+//
+// %s and %d take the arguments in order; %1$s and %2$d pick one by position
+// (from 1), as translations that reorder the English arguments need:
+// "%2$s von %1$s". Sequential conversions count only among themselves, so
+// mixing the two forms (which lintMessage reports) still reads predictably.
+//
+// Arguments keep their JSON type: numbers are formatted as numbers, strings
+// holding a number are read as one by numeric conversions, and anything
+// else is written as text. A conversion without an argument is left as
+// written.
+//
+// With a locale, the decimal conversions (d i u f F, and g G without an
+// exponent) use its grouping and decimal separators: "%.1f" of 1234.5 is
+// "1 234,5" in French. Without one the output is C's.
+use serde_json::Value;
+
+use super::numbers;
+
+#[derive(Default)]
+struct Spec {
+    position: Option<usize>,
+    left: bool,
+    zero: bool,
+    plus: bool,
+    alternate: bool,
+    width: Option<Count>,
+    precision: Option<Count>,
+    conversion: u8,
+    /// Bytes of the template the conversion takes up
+    length: usize,
+}
+
+#[derive(Clone, Copy)]
+enum Count {
+    Fixed(usize),
+    /// "*": the next sequential argument
+    Argument,
+}
+
+pub(crate) fn format(template: &str, args: &[Value], locale: Option<&str>) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut next = 0;
+    let mut rest = template;
+    while let Some(start) = rest.find('%') {
+        out.push_str(&rest[..start]);
+        let tail = &rest[start..];
+        if let Some(after) = tail.strip_prefix("%%") {
+            out.push('%');
+            rest = after;
+            continue;
+        }
+        let Some(spec) = parse(tail) else {
+            out.push('%');
+            rest = &tail[1..];
+            continue;
+        };
+        let mut sequential = || {
+            next += 1;
+            args.get(next - 1)
+        };
+        let mut count = |count: Option<Count>| match count {
+            Some(Count::Fixed(count)) => Some(Some(count)),
+            Some(Count::Argument) => sequential().and_then(Value::as_f64).map(|count| Some(count.max(0.0) as usize)),
+            None => Some(None),
+        };
+        let (width, precision) = (count(spec.width), count(spec.precision));
+        let arg = match spec.position {
+            Some(position) => args.get(position - 1),
+            None => sequential(),
+        };
+        match (arg, width, precision) {
+            (Some(arg), Some(width), Some(precision)) => out.push_str(&convert(&spec, arg, width, precision, locale)),
+            _ => out.push_str(&tail[..spec.length]),
+        }
+        rest = &tail[spec.length..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// The conversion at the start of text (which begins with '%')
+fn parse(text: &str) -> Option<Spec> {
+    let bytes = text.as_bytes();
+    let digits = |from: usize| bytes[from..].iter().take_while(|b| b.is_ascii_digit()).count();
+    let number = |from: usize, count: usize| text[from..from + count].parse::<usize>().ok();
+    let mut spec = Spec::default();
+    let mut index = 1;
+
+    let leading = digits(index);
+    if leading > 0 && bytes.get(index + leading) == Some(&b'$') {
+        spec.position = Some(number(index, leading).filter(|&position| position > 0)?);
+        index += leading + 1;
+    }
+    while let Some(flag) = bytes.get(index).filter(|b| b"-+0#'".contains(b)) {
+        match flag {
+            b'-' => spec.left = true,
+            b'0' => spec.zero = true,
+            b'+' => spec.plus = true,
+            b'#' => spec.alternate = true,
+            _ => {}
+        }
+        index += 1;
+    }
+    let count = |index: &mut usize| {
+        if bytes.get(*index) == Some(&b'*') {
+            *index += 1;
+            return Some(Count::Argument);
+        }
+        let length = digits(*index);
+        let count = number(*index, length).map(Count::Fixed);
+        *index += length;
+        count
+    };
+    spec.width = count(&mut index);
+    if bytes.get(index) == Some(&b'.') {
+        index += 1;
+        spec.precision = Some(count(&mut index).unwrap_or(Count::Fixed(0)));
+    }
+    index += bytes[index..].iter().take_while(|b| b"hlLqjzt".contains(b)).count();
+
+    spec.conversion = *bytes.get(index).filter(|b| b"diuxXofFeEgGsc".contains(b))?;
+    spec.length = index + 1;
+    Some(spec)
+}
+
+fn convert(spec: &Spec, arg: &Value, width: Option<usize>, precision: Option<usize>, locale: Option<&str>) -> String {
+    let number = match arg {
+        Value::Number(number) => number.as_f64(),
+        Value::String(text) => text.trim().parse::<f64>().ok(),
+        _ => None,
+    };
+    let (sign, body) = match (spec.conversion, number) {
+        (b's', _) => ("", text(arg, precision)),
+        (b'c', _) => ("", character(arg)),
+        (_, None) => ("", text(arg, None)),
+        (_, Some(number)) if !number.is_finite() => {
+            let body = if number.is_nan() { "nan" } else { "inf" };
+            let upper = spec.conversion.is_ascii_uppercase();
+            (sign(spec, number), if upper { body.to_uppercase() } else { body.to_string() })
+        }
+        (b'd' | b'i' | b'u', Some(number)) => {
+            let integer = integer(arg, number);
+            (sign(spec, integer as f64), min_digits(integer.unsigned_abs().to_string(), precision))
+        }
+        (b'x' | b'X' | b'o', Some(number)) => {
+            // Negative numbers are written as their 64-bit two's complement, as C does
+            let integer = integer(arg, number) as i64 as u64;
+            let (digits, prefix) = match spec.conversion {
+                b'x' => (format!("{:x}", integer), "0x"),
+                b'X' => (format!("{:X}", integer), "0X"),
+                _ => (format!("{:o}", integer), "0"),
+            };
+            let digits = min_digits(digits, precision);
+            match spec.alternate && integer != 0 && !(prefix == "0" && digits.starts_with('0')) {
+                true => ("", format!("{}{}", prefix, digits)),
+                false => ("", digits),
+            }
+        }
+        (b'f' | b'F', Some(number)) => {
+            let mut body = format!("{:.*}", precision.unwrap_or(6), number.abs());
+            if spec.alternate && !body.contains('.') {
+                body.push('.');
+            }
+            (sign(spec, number), body)
+        }
+        (b'e' | b'E', Some(number)) => {
+            let body = exponential(number.abs(), precision.unwrap_or(6), spec.alternate);
+            let body = if spec.conversion == b'E' { body.to_uppercase() } else { body };
+            (sign(spec, number), body)
+        }
+        (_, Some(number)) => {
+            let body = general(number.abs(), precision.unwrap_or(6), spec.alternate);
+            let body = if spec.conversion == b'G' { body.to_uppercase() } else { body };
+            (sign(spec, number), body)
+        }
+    };
+    let numeric = number.is_some_and(f64::is_finite) && !matches!(spec.conversion, b's' | b'c');
+    // numbers::format leaves exponents and a bare trailing point as they are
+    let body = match locale {
+        Some(locale) if numeric && b"diufFgG".contains(&spec.conversion) => numbers::format(&body, locale),
+        _ => body,
+    };
+    pad(spec, sign, &body, width, numeric)
+}
+
+/// An integer argument, exact for JSON integers beyond f64's precision
+fn integer(arg: &Value, number: f64) -> i128 {
+    match arg {
+        Value::Number(value) if value.is_i64() => value.as_i64().map_or(0, i128::from),
+        Value::Number(value) if value.is_u64() => value.as_u64().map_or(0, i128::from),
+        _ => number.trunc() as i128,
+    }
+}
+
+fn sign(spec: &Spec, number: f64) -> &'static str {
+    match number.is_sign_negative() && number != 0.0 {
+        true => "-",
+        false if spec.plus => "+",
+        false => "",
+    }
+}
+
+fn text(arg: &Value, precision: Option<usize>) -> String {
+    let text = match arg {
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    };
+    match precision {
+        Some(precision) => text.chars().take(precision).collect(),
+        None => text,
+    }
+}
+
+/// %c: the first character of a string, or the character with a number's code
+fn character(arg: &Value) -> String {
+    match arg {
+        Value::Number(code) => code
+            .as_u64()
+            .and_then(|code| char::from_u32(code as u32))
+            .map(String::from)
+            .unwrap_or_default(),
+        other => text(other, Some(1)),
+    }
+}
+
+/// Left-pad digits with zeros to a precision
+fn min_digits(digits: String, precision: Option<usize>) -> String {
+    match precision {
+        Some(precision) if digits.len() < precision => format!("{}{}", "0".repeat(precision - digits.len()), digits),
+        Some(0) if digits == "0" => String::new(),
+        _ => digits,
+    }
+}
+
+/// C's %e body for a non-negative number: "1.500000e+03"
+fn exponential(number: f64, precision: usize, alternate: bool) -> String {
+    let formatted = format!("{:.*e}", precision, number);
+    let (mantissa, exponent) = formatted.split_once('e').unwrap_or((&formatted, "0"));
+    let exponent: i32 = exponent.parse().unwrap_or(0);
+    let point = if alternate && precision == 0 { "." } else { "" };
+    format!("{}{}e{}{:02}", mantissa, point, if exponent < 0 { '-' } else { '+' }, exponent.abs())
+}
+
+/// C's %g body: %e or %f by the exponent, with precision significant digits
+/// and trailing zeros removed unless alternate
+fn general(number: f64, precision: usize, alternate: bool) -> String {
+    let precision = precision.max(1);
+    let exponent = match number {
+        0.0 => 0,
+        _ => {
+            let formatted = format!("{:.*e}", precision - 1, number);
+            formatted.split_once('e').and_then(|(_, exponent)| exponent.parse::<i32>().ok()).unwrap_or(0)
+        }
+    };
+    let body = if exponent < -4 || exponent >= precision as i32 {
+        exponential(number, precision - 1, alternate)
+    } else {
+        format!("{:.*}", (precision as i32 - 1 - exponent) as usize, number)
+    };
+    if alternate {
+        return body;
+    }
+    let (mantissa, exponent) = match body.find('e') {
+        Some(at) => body.split_at(at),
+        None => (body.as_str(), ""),
+    };
+    let mantissa = match mantissa.contains('.') {
+        true => mantissa.trim_end_matches('0').trim_end_matches('.'),
+        false => mantissa,
+    };
+    format!("{}{}", mantissa, exponent)
+}
+
+/// Pad to the width: spaces on the left, on the right with "-", or zeros
+/// after the sign and any 0x prefix with "0" for numbers
+fn pad(spec: &Spec, sign: &str, body: &str, width: Option<usize>, numeric: bool) -> String {
+    let length = sign.chars().count() + body.chars().count();
+    let fill = width.unwrap_or(0).saturating_sub(length);
+    if fill == 0 {
+        return format!("{}{}", sign, body);
+    }
+    let integer_precision = spec.precision.is_some() && matches!(spec.conversion, b'd' | b'i' | b'u' | b'x' | b'X' | b'o');
+    if spec.left {
+        format!("{}{}{}", sign, body, " ".repeat(fill))
+    } else if spec.zero && numeric && !integer_precision {
+        let (prefix, digits) = match body.get(..2) {
+            Some("0x" | "0X") => body.split_at(2),
+            _ => ("", body),
+        };
+        format!("{}{}{}{}", sign, prefix, "0".repeat(fill), digits)
+    } else {
+        format!("{}{}{}", " ".repeat(fill), sign, body)
+    }
+}
@@ -0,0 +1,218 @@
+// Precompiled message templates
+//
+// A message is parsed once into literal runs and placeholders: %d / %s for a
+// plural count and {{name}} for values; {{user.name}} reads into nested
+// objects and {{name, format}} formats the value (see formatters.rs). Values
+// are escaped for HTML unless the placeholder is {{{name}}} or i18next's
+// {{- name}}. Rendering is a single pass into a buffer sized from the literal
+// text. "%%d" keeps its first '%' and counts the rest, as the string
+// replacement it stands in for did.
+use serde_json::{Map, Value};
+use wasm_bindgen::JsValue;
+
+use super::formatters::{self, Formatters};
+use super::{I18nError, MissingVariables};
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    Literal(String),
+    /// %d or %s
+    Count(&'static str),
+    /// {{name}}, with the text as written for when no value is given;
+    /// escape is false for {{{name}}} and {{- name}}
+    Value {
+        name: String,
+        raw: String,
+        escape: bool,
+        format: Option<String>,
+    },
+}
+
+/// How values are written: their escaping, the locale and registered
+/// formatters for {{name, format}}, and what a placeholder without a value
+/// becomes ("{name}" in the marker is replaced by its name)
+#[derive(Clone, Copy)]
+pub(crate) struct Interpolation<'a> {
+    pub escape: Escape<'a>,
+    pub locale: &'a str,
+    pub formatters: Option<&'a Formatters>,
+    pub missing: MissingVariables,
+    pub marker: &'a str,
+}
+
+impl Interpolation<'_> {
+    /// HTML escaping and the built-in formatters for a locale; placeholders
+    /// without a value are kept
+    pub fn html(locale: &str) -> Interpolation<'_> {
+        Interpolation {
+            escape: Escape::Html,
+            locale,
+            formatters: None,
+            missing: MissingVariables::Keep,
+            marker: "",
+        }
+    }
+}
+
+/// How values are written into escaping placeholders
+#[derive(Clone, Copy)]
+pub(crate) enum Escape<'a> {
+    Verbatim,
+    Html,
+    /// A JS callback from the value to its escaped text; HTML escaping is
+    /// used when it throws or returns something other than a string
+    Custom(&'a js_sys::Function),
+}
+
+impl Escape<'_> {
+    fn write(self, out: &mut String, text: &str) {
+        match self {
+            Escape::Verbatim => out.push_str(text),
+            Escape::Html => escape_html(out, text),
+            Escape::Custom(callback) => {
+                match callback.call1(&JsValue::NULL, &JsValue::from_str(text)).ok().and_then(|value| value.as_string()) {
+                    Some(escaped) => out.push_str(&escaped),
+                    None => escape_html(out, text),
+                }
+            }
+        }
+    }
+}
+
+/// Replace the characters that are markup in HTML text and attribute values
+pub(crate) fn escape_html(out: &mut String, text: &str) {
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            c => out.push(c),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) struct Template {
+    tokens: Vec<Token>,
+    literal_len: usize,
+}
+
+impl Template {
+    pub fn parse(source: &str) -> Template {
+        let mut tokens = Vec::new();
+        let mut literal = String::new();
+        let mut rest = source;
+        while let Some(start) = rest.find(['%', '{']) {
+            literal.push_str(&rest[..start]);
+            let tail = &rest[start..];
+            let consumed = if let Some(count) = ["%d", "%s"].into_iter().find(|count| tail.starts_with(count)) {
+                flush(&mut tokens, &mut literal);
+                tokens.push(Token::Count(count));
+                2
+            } else if let Some(close) = tail.strip_prefix("{{{").and_then(|inner| inner.find("}}}")) {
+                flush(&mut tokens, &mut literal);
+                let (name, format) = placeholder(&tail[3..3 + close]);
+                tokens.push(Token::Value {
+                    name,
+                    raw: tail[..close + 6].to_string(),
+                    escape: false,
+                    format,
+                });
+                close + 6
+            } else if let Some(close) = tail.strip_prefix("{{").and_then(|inner| inner.find("}}")) {
+                let inner = tail[2..2 + close].trim();
+                let (name, format) = placeholder(inner.trim_start_matches('-'));
+                flush(&mut tokens, &mut literal);
+                tokens.push(Token::Value {
+                    name,
+                    raw: tail[..close + 4].to_string(),
+                    escape: !inner.starts_with('-'),
+                    format,
+                });
+                close + 4
+            } else {
+                literal.push_str(&tail[..1]);
+                1
+            };
+            rest = &tail[consumed..];
+        }
+        literal.push_str(rest);
+        flush(&mut tokens, &mut literal);
+
+        let literal_len = tokens
+            .iter()
+            .map(|token| match token {
+                Token::Literal(text) => text.len(),
+                _ => 0,
+            })
+            .sum();
+        Template { tokens, literal_len }
+    }
+
+    /// Fill the placeholders: %d / %s with count (left as written without
+    /// one) and {{name}} from values; one absent or null is handled as
+    /// interpolation.missing says, failing with MissingVariable for Error
+    pub fn render(&self, count: Option<&str>, values: &Map<String, Value>, interpolation: Interpolation) -> Result<String, I18nError> {
+        let mut out = String::with_capacity(self.literal_len + 8 * self.tokens.len());
+        for token in &self.tokens {
+            match token {
+                Token::Literal(text) => out.push_str(text),
+                Token::Count(spec) => out.push_str(count.unwrap_or(spec)),
+                Token::Value { name, raw, escape, format } => {
+                    let escape = if *escape { interpolation.escape } else { Escape::Verbatim };
+                    let value = match lookup(values, name) {
+                        Some(Value::Null) | None => {
+                            match interpolation.missing {
+                                MissingVariables::Keep => out.push_str(raw),
+                                MissingVariables::Empty => {}
+                                MissingVariables::Marker => out.push_str(&interpolation.marker.replace("{name}", name)),
+                                MissingVariables::Error => return Err(I18nError::MissingVariable { name: name.clone() }),
+                            }
+                            continue;
+                        }
+                        Some(value) => value,
+                    };
+                    let formatted = format.as_deref().and_then(|format| {
+                        formatters::apply(format, value, interpolation.locale, interpolation.formatters)
+                    });
+                    match (formatted, value) {
+                        (Some(text), _) => escape.write(&mut out, &text),
+                        (None, Value::String(text)) => escape.write(&mut out, text),
+                        (None, other) => escape.write(&mut out, &other.to_string()),
+                    }
+                }
+            }
+        }
+        Ok(out)
+    }
+}
+
+/// The name and format of "name" or "name, format"
+fn placeholder(inner: &str) -> (String, Option<String>) {
+    match inner.split_once(',') {
+        Some((name, format)) => (name.trim().to_string(), Some(format.trim().to_string()).filter(|f| !f.is_empty())),
+        None => (inner.trim().to_string(), None),
+    }
+}
+
+/// A value by name: a key written with dots as it is, otherwise a path
+/// into nested objects and arrays ("user.name", "items.0.title")
+fn lookup<'v>(values: &'v Map<String, Value>, name: &str) -> Option<&'v Value> {
+    if let Some(value) = values.get(name) {
+        return Some(value);
+    }
+    let (first, rest) = name.split_once('.')?;
+    rest.split('.').try_fold(values.get(first)?, |value, part| match value {
+        Value::Object(object) => object.get(part),
+        Value::Array(items) => items.get(part.parse::<usize>().ok()?),
+        _ => None,
+    })
+}
+
+fn flush(tokens: &mut Vec<Token>, literal: &mut String) {
+    if !literal.is_empty() {
+        tokens.push(Token::Literal(std::mem::take(literal)));
+    }
+}
@@ -0,0 +1,39 @@
+// TMX (Translation Memory eXchange) files
+//
+// <tmx><body><tu><tuv xml:lang="en"><seg>..</seg></tuv>..</tu></body></tmx>
+// Each <tu> becomes one unit mapping canonical locale tags to segment text.
+// TMX 1.1 "lang" attributes are accepted too. Inline markup elements
+// (<bpt>, <ph>, ...) contribute the native code they carry, so a segment
+// reads as the original text.
+use std::collections::HashMap;
+
+use super::canonicalize_locale;
+use super::xml;
+
+/// One translation unit: locale -> segment
+pub(crate) type Unit = HashMap<String, String>;
+
+pub(crate) fn parse(text: &str) -> Result<Vec<Unit>, String> {
+    let root = xml::parse(text)?;
+    if root.name != "tmx" {
+        return Err(format!("expected <tmx>, found <{}>", root.name));
+    }
+    let body = root.child("body").ok_or("<tmx> without a <body>")?;
+
+    let mut units = Vec::new();
+    for tu in body.elements().filter(|element| element.name == "tu") {
+        let mut unit = Unit::new();
+        for tuv in tu.elements().filter(|element| element.name == "tuv") {
+            let locale = tuv
+                .attribute("xml:lang")
+                .or_else(|| tuv.attribute("lang"))
+                .ok_or("<tuv> without xml:lang")?;
+            let segment = tuv.child("seg").ok_or("<tuv> without a <seg>")?;
+            unit.insert(canonicalize_locale(locale), segment.text());
+        }
+        if unit.len() > 1 {
+            units.push(unit);
+        }
+    }
+    Ok(units)
+}
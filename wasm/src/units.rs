@@ -0,0 +1,319 @@
+// Unit patterns for NumberFormat's unit style
+//
+// Forms follow CLDR's units data: a pattern per plural category, where "{0}"
+// is the formatted number ("{0} kilometers", "{0} км", "{0}°C"). A locale
+// without a narrow pattern uses its short one, and a locale without the
+// unit at all uses the English short one, as symbols like "km" are shared.
+// Long names are given for the languages below; other languages get the
+// English short symbols.
+
+/// (unit, long, short, narrow) forms by plural category; an empty list
+/// falls back as above
+type Unit = (&'static str, Forms, Forms, Forms);
+type Forms = &'static [(&'static str, &'static str)];
+
+/// Units that can be formatted, as CLDR names them
+pub(crate) const UNITS: &[&str] = &[
+    "kilometer", "meter", "centimeter", "mile", "foot", "inch", "kilogram", "gram", "pound", "celsius",
+    "fahrenheit", "liter", "byte", "kilobyte", "megabyte", "gigabyte", "second", "minute", "hour", "day",
+    "kilometer-per-hour", "percent",
+];
+
+const EN: &[Unit] = &[
+    ("kilometer", &[("one", "{0} kilometer"), ("other", "{0} kilometers")], &[("other", "{0} km")], &[("other", "{0}km")]),
+    ("meter", &[("one", "{0} meter"), ("other", "{0} meters")], &[("other", "{0} m")], &[("other", "{0}m")]),
+    ("centimeter", &[("one", "{0} centimeter"), ("other", "{0} centimeters")], &[("other", "{0} cm")], &[("other", "{0}cm")]),
+    ("mile", &[("one", "{0} mile"), ("other", "{0} miles")], &[("other", "{0} mi")], &[("other", "{0}mi")]),
+    ("foot", &[("one", "{0} foot"), ("other", "{0} feet")], &[("other", "{0} ft")], &[("other", "{0}′")]),
+    ("inch", &[("one", "{0} inch"), ("other", "{0} inches")], &[("other", "{0} in")], &[("other", "{0}″")]),
+    ("kilogram", &[("one", "{0} kilogram"), ("other", "{0} kilograms")], &[("other", "{0} kg")], &[("other", "{0}kg")]),
+    ("gram", &[("one", "{0} gram"), ("other", "{0} grams")], &[("other", "{0} g")], &[("other", "{0}g")]),
+    ("pound", &[("one", "{0} pound"), ("other", "{0} pounds")], &[("other", "{0} lb")], &[("other", "{0}lb")]),
+    (
+        "celsius",
+        &[("one", "{0} degree Celsius"), ("other", "{0} degrees Celsius")],
+        &[("other", "{0}°C")],
+        &[("other", "{0}°C")],
+    ),
+    (
+        "fahrenheit",
+        &[("one", "{0} degree Fahrenheit"), ("other", "{0} degrees Fahrenheit")],
+        &[("other", "{0}°F")],
+        &[("other", "{0}°")],
+    ),
+    ("liter", &[("one", "{0} liter"), ("other", "{0} liters")], &[("other", "{0} L")], &[("other", "{0}L")]),
+    ("byte", &[("one", "{0} byte"), ("other", "{0} bytes")], &[("other", "{0} byte")], &[("other", "{0}B")]),
+    ("kilobyte", &[("one", "{0} kilobyte"), ("other", "{0} kilobytes")], &[("other", "{0} kB")], &[("other", "{0}kB")]),
+    ("megabyte", &[("one", "{0} megabyte"), ("other", "{0} megabytes")], &[("other", "{0} MB")], &[("other", "{0}MB")]),
+    ("gigabyte", &[("one", "{0} gigabyte"), ("other", "{0} gigabytes")], &[("other", "{0} GB")], &[("other", "{0}GB")]),
+    ("second", &[("one", "{0} second"), ("other", "{0} seconds")], &[("other", "{0} sec")], &[("other", "{0}s")]),
+    ("minute", &[("one", "{0} minute"), ("other", "{0} minutes")], &[("other", "{0} min")], &[("other", "{0}m")]),
+    ("hour", &[("one", "{0} hour"), ("other", "{0} hours")], &[("other", "{0} hr")], &[("other", "{0}h")]),
+    ("day", &[("one", "{0} day"), ("other", "{0} days")], &[("one", "{0} day"), ("other", "{0} days")], &[("other", "{0}d")]),
+    (
+        "kilometer-per-hour",
+        &[("one", "{0} kilometer per hour"), ("other", "{0} kilometers per hour")],
+        &[("other", "{0} km/h")],
+        &[("other", "{0}km/h")],
+    ),
+    ("percent", &[("other", "{0} percent")], &[("other", "{0}%")], &[("other", "{0}%")]),
+];
+
+const DE: &[Unit] = &[
+    ("kilometer", &[("other", "{0} Kilometer")], &[], &[]),
+    ("meter", &[("other", "{0} Meter")], &[], &[]),
+    ("centimeter", &[("other", "{0} Zentimeter")], &[], &[]),
+    ("mile", &[("one", "{0} Meile"), ("other", "{0} Meilen")], &[], &[]),
+    ("foot", &[("other", "{0} Fuß")], &[], &[]),
+    ("inch", &[("other", "{0} Zoll")], &[], &[]),
+    ("kilogram", &[("other", "{0} Kilogramm")], &[], &[]),
+    ("gram", &[("other", "{0} Gramm")], &[], &[]),
+    ("pound", &[("other", "{0} Pfund")], &[], &[]),
+    ("celsius", &[("other", "{0} Grad Celsius")], &[("other", "{0} °C")], &[("other", "{0}°C")]),
+    ("fahrenheit", &[("other", "{0} Grad Fahrenheit")], &[("other", "{0} °F")], &[("other", "{0}°F")]),
+    ("liter", &[("other", "{0} Liter")], &[("other", "{0} l")], &[]),
+    ("byte", &[("other", "{0} Byte")], &[("other", "{0} Byte")], &[("other", "{0}B")]),
+    ("kilobyte", &[("other", "{0} Kilobyte")], &[], &[]),
+    ("megabyte", &[("other", "{0} Megabyte")], &[], &[]),
+    ("gigabyte", &[("other", "{0} Gigabyte")], &[], &[]),
+    ("second", &[("one", "{0} Sekunde"), ("other", "{0} Sekunden")], &[("other", "{0} Sek.")], &[("other", "{0} s")]),
+    ("minute", &[("one", "{0} Minute"), ("other", "{0} Minuten")], &[("other", "{0} Min.")], &[("other", "{0} Min.")]),
+    ("hour", &[("one", "{0} Stunde"), ("other", "{0} Stunden")], &[("other", "{0} Std.")], &[("other", "{0} Std.")]),
+    ("day", &[("one", "{0} Tag"), ("other", "{0} Tage")], &[("other", "{0} Tg.")], &[("other", "{0} T")]),
+    ("kilometer-per-hour", &[("other", "{0} Kilometer pro Stunde")], &[], &[]),
+    ("percent", &[("other", "{0} Prozent")], &[("other", "{0} %")], &[("other", "{0} %")]),
+];
+
+const ES: &[Unit] = &[
+    ("kilometer", &[("one", "{0} kilómetro"), ("other", "{0} kilómetros")], &[], &[]),
+    ("meter", &[("one", "{0} metro"), ("other", "{0} metros")], &[], &[]),
+    ("centimeter", &[("one", "{0} centímetro"), ("other", "{0} centímetros")], &[], &[]),
+    ("mile", &[("one", "{0} milla"), ("other", "{0} millas")], &[], &[]),
+    ("foot", &[("one", "{0} pie"), ("other", "{0} pies")], &[], &[]),
+    ("inch", &[("one", "{0} pulgada"), ("other", "{0} pulgadas")], &[], &[]),
+    ("kilogram", &[("one", "{0} kilogramo"), ("other", "{0} kilogramos")], &[], &[]),
+    ("gram", &[("one", "{0} gramo"), ("other", "{0} gramos")], &[], &[]),
+    ("pound", &[("one", "{0} libra"), ("other", "{0} libras")], &[], &[]),
+    ("celsius", &[("one", "{0} grado Celsius"), ("other", "{0} grados Celsius")], &[("other", "{0} °C")], &[]),
+    ("fahrenheit", &[("one", "{0} grado Fahrenheit"), ("other", "{0} grados Fahrenheit")], &[("other", "{0} °F")], &[]),
+    ("liter", &[("one", "{0} litro"), ("other", "{0} litros")], &[("other", "{0} l")], &[]),
+    ("byte", &[("one", "{0} byte"), ("other", "{0} bytes")], &[("other", "{0} B")], &[]),
+    ("kilobyte", &[("one", "{0} kilobyte"), ("other", "{0} kilobytes")], &[], &[]),
+    ("megabyte", &[("one", "{0} megabyte"), ("other", "{0} megabytes")], &[], &[]),
+    ("gigabyte", &[("one", "{0} gigabyte"), ("other", "{0} gigabytes")], &[], &[]),
+    ("second", &[("one", "{0} segundo"), ("other", "{0} segundos")], &[("other", "{0} s")], &[]),
+    ("minute", &[("one", "{0} minuto"), ("other", "{0} minutos")], &[("other", "{0} min")], &[]),
+    ("hour", &[("one", "{0} hora"), ("other", "{0} horas")], &[("other", "{0} h")], &[]),
+    ("day", &[("one", "{0} día"), ("other", "{0} días")], &[("other", "{0} d")], &[]),
+    ("kilometer-per-hour", &[("one", "{0} kilómetro por hora"), ("other", "{0} kilómetros por hora")], &[], &[]),
+    ("percent", &[("other", "{0} por ciento")], &[("other", "{0} %")], &[("other", "{0}%")]),
+];
+
+const FR: &[Unit] = &[
+    ("kilometer", &[("one", "{0} kilomètre"), ("other", "{0} kilomètres")], &[], &[]),
+    ("meter", &[("one", "{0} mètre"), ("other", "{0} mètres")], &[], &[]),
+    ("centimeter", &[("one", "{0} centimètre"), ("other", "{0} centimètres")], &[], &[]),
+    ("mile", &[("one", "{0} mile"), ("other", "{0} miles")], &[], &[]),
+    ("foot", &[("one", "{0} pied"), ("other", "{0} pieds")], &[("other", "{0} pi")], &[]),
+    ("inch", &[("one", "{0} pouce"), ("other", "{0} pouces")], &[("other", "{0} po")], &[]),
+    ("kilogram", &[("one", "{0} kilogramme"), ("other", "{0} kilogrammes")], &[], &[]),
+    ("gram", &[("one", "{0} gramme"), ("other", "{0} grammes")], &[], &[]),
+    ("pound", &[("one", "{0} livre"), ("other", "{0} livres")], &[], &[]),
+    ("celsius", &[("one", "{0} degré Celsius"), ("other", "{0} degrés Celsius")], &[("other", "{0} °C")], &[("other", "{0}°C")]),
+    ("fahrenheit", &[("one", "{0} degré Fahrenheit"), ("other", "{0} degrés Fahrenheit")], &[("other", "{0} °F")], &[("other", "{0}°F")]),
+    ("liter", &[("one", "{0} litre"), ("other", "{0} litres")], &[("other", "{0} l")], &[]),
+    ("byte", &[("one", "{0} octet"), ("other", "{0} octets")], &[("other", "{0} o")], &[("other", "{0}o")]),
+    ("kilobyte", &[("one", "{0} kilooctet"), ("other", "{0} kilooctets")], &[("other", "{0} ko")], &[("other", "{0}ko")]),
+    ("megabyte", &[("one", "{0} mégaoctet"), ("other", "{0} mégaoctets")], &[("other", "{0} Mo")], &[("other", "{0}Mo")]),
+    ("gigabyte", &[("one", "{0} gigaoctet"), ("other", "{0} gigaoctets")], &[("other", "{0} Go")], &[("other", "{0}Go")]),
+    ("second", &[("one", "{0} seconde"), ("other", "{0} secondes")], &[("other", "{0} s")], &[("other", "{0}s")]),
+    ("minute", &[("one", "{0} minute"), ("other", "{0} minutes")], &[("other", "{0} min")], &[("other", "{0}min")]),
+    ("hour", &[("one", "{0} heure"), ("other", "{0} heures")], &[("other", "{0} h")], &[("other", "{0}h")]),
+    ("day", &[("one", "{0} jour"), ("other", "{0} jours")], &[("other", "{0} j")], &[("other", "{0}j")]),
+    ("kilometer-per-hour", &[("one", "{0} kilomètre à l’heure"), ("other", "{0} kilomètres à l’heure")], &[], &[]),
+    ("percent", &[("other", "{0} pour cent")], &[("other", "{0} %")], &[("other", "{0}%")]),
+];
+
+const RU: &[Unit] = &[
+    (
+        "kilometer",
+        &[("one", "{0} километр"), ("few", "{0} километра"), ("many", "{0} километров"), ("other", "{0} километра")],
+        &[("other", "{0} км")],
+        &[("other", "{0} км")],
+    ),
+    (
+        "meter",
+        &[("one", "{0} метр"), ("few", "{0} метра"), ("many", "{0} метров"), ("other", "{0} метра")],
+        &[("other", "{0} м")],
+        &[("other", "{0} м")],
+    ),
+    (
+        "centimeter",
+        &[("one", "{0} сантиметр"), ("few", "{0} сантиметра"), ("many", "{0} сантиметров"), ("other", "{0} сантиметра")],
+        &[("other", "{0} см")],
+        &[("other", "{0} см")],
+    ),
+    (
+        "mile",
+        &[("one", "{0} миля"), ("few", "{0} мили"), ("many", "{0} миль"), ("other", "{0} мили")],
+        &[("other", "{0} ми")],
+        &[("other", "{0} ми")],
+    ),
+    (
+        "foot",
+        &[("one", "{0} фут"), ("few", "{0} фута"), ("many", "{0} футов"), ("other", "{0} фута")],
+        &[("other", "{0} фт")],
+        &[("other", "{0} фт")],
+    ),
+    (
+        "inch",
+        &[("one", "{0} дюйм"), ("few", "{0} дюйма"), ("many", "{0} дюймов"), ("other", "{0} дюйма")],
+        &[("other", "{0} дюйм.")],
+        &[("other", "{0} дюйм.")],
+    ),
+    (
+        "kilogram",
+        &[("one", "{0} килограмм"), ("few", "{0} килограмма"), ("many", "{0} килограммов"), ("other", "{0} килограмма")],
+        &[("other", "{0} кг")],
+        &[("other", "{0} кг")],
+    ),
+    (
+        "gram",
+        &[("one", "{0} грамм"), ("few", "{0} грамма"), ("many", "{0} граммов"), ("other", "{0} грамма")],
+        &[("other", "{0} г")],
+        &[("other", "{0} г")],
+    ),
+    (
+        "pound",
+        &[("one", "{0} фунт"), ("few", "{0} фунта"), ("many", "{0} фунтов"), ("other", "{0} фунта")],
+        &[("other", "{0} фнт")],
+        &[("other", "{0} фнт")],
+    ),
+    (
+        "celsius",
+        &[
+            ("one", "{0} градус Цельсия"),
+            ("few", "{0} градуса Цельсия"),
+            ("many", "{0} градусов Цельсия"),
+            ("other", "{0} градуса Цельсия"),
+        ],
+        &[("other", "{0} °C")],
+        &[("other", "{0}°")],
+    ),
+    (
+        "fahrenheit",
+        &[
+            ("one", "{0} градус Фаренгейта"),
+            ("few", "{0} градуса Фаренгейта"),
+            ("many", "{0} градусов Фаренгейта"),
+            ("other", "{0} градуса Фаренгейта"),
+        ],
+        &[("other", "{0} °F")],
+        &[("other", "{0}°F")],
+    ),
+    (
+        "liter",
+        &[("one", "{0} литр"), ("few", "{0} литра"), ("many", "{0} литров"), ("other", "{0} литра")],
+        &[("other", "{0} л")],
+        &[("other", "{0} л")],
+    ),
+    (
+        "byte",
+        &[("one", "{0} байт"), ("few", "{0} байта"), ("many", "{0} байт"), ("other", "{0} байта")],
+        &[("other", "{0} Б")],
+        &[("other", "{0} Б")],
+    ),
+    (
+        "kilobyte",
+        &[("one", "{0} килобайт"), ("few", "{0} килобайта"), ("many", "{0} килобайт"), ("other", "{0} килобайта")],
+        &[("other", "{0} КБ")],
+        &[("other", "{0} КБ")],
+    ),
+    (
+        "megabyte",
+        &[("one", "{0} мегабайт"), ("few", "{0} мегабайта"), ("many", "{0} мегабайт"), ("other", "{0} мегабайта")],
+        &[("other", "{0} МБ")],
+        &[("other", "{0} МБ")],
+    ),
+    (
+        "gigabyte",
+        &[("one", "{0} гигабайт"), ("few", "{0} гигабайта"), ("many", "{0} гигабайт"), ("other", "{0} гигабайта")],
+        &[("other", "{0} ГБ")],
+        &[("other", "{0} ГБ")],
+    ),
+    (
+        "second",
+        &[("one", "{0} секунда"), ("few", "{0} секунды"), ("many", "{0} секунд"), ("other", "{0} секунды")],
+        &[("other", "{0} с")],
+        &[("other", "{0} с")],
+    ),
+    (
+        "minute",
+        &[("one", "{0} минута"), ("few", "{0} минуты"), ("many", "{0} минут"), ("other", "{0} минуты")],
+        &[("other", "{0} мин")],
+        &[("other", "{0} мин")],
+    ),
+    (
+        "hour",
+        &[("one", "{0} час"), ("few", "{0} часа"), ("many", "{0} часов"), ("other", "{0} часа")],
+        &[("other", "{0} ч")],
+        &[("other", "{0} ч")],
+    ),
+    (
+        "day",
+        &[("one", "{0} день"), ("few", "{0} дня"), ("many", "{0} дней"), ("other", "{0} дня")],
+        &[("other", "{0} дн.")],
+        &[("other", "{0} д")],
+    ),
+    (
+        "kilometer-per-hour",
+        &[
+            ("one", "{0} километр в час"),
+            ("few", "{0} километра в час"),
+            ("many", "{0} километров в час"),
+            ("other", "{0} километра в час"),
+        ],
+        &[("other", "{0} км/ч")],
+        &[("other", "{0} км/ч")],
+    ),
+    (
+        "percent",
+        &[("one", "{0} процент"), ("few", "{0} процента"), ("many", "{0} процентов"), ("other", "{0} процента")],
+        &[("other", "{0} %")],
+        &[("other", "{0}%")],
+    ),
+];
+
+const LOCALES: &[(&str, &[Unit])] = &[("de", DE), ("en", EN), ("es", ES), ("fr", FR), ("ru", RU)];
+
+/// Unit width, as UnitDisplay
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Width {
+    Long,
+    Short,
+    Narrow,
+}
+
+/// A formatted number in a unit's pattern for a language, width and plural
+/// category
+pub(crate) fn apply(unit: &str, language: &str, width: Width, category: &str, number: &str) -> String {
+    let forms = |units: &[Unit], width: Width| -> Option<Forms> {
+        let &(_, long, short, narrow) = units.iter().find(|(name, ..)| *name == unit)?;
+        let forms = match width {
+            Width::Long => long,
+            Width::Short => short,
+            Width::Narrow => narrow,
+        };
+        (!forms.is_empty()).then_some(forms)
+    };
+    let units = LOCALES.iter().find(|(candidate, _)| *candidate == language).map_or(&[][..], |(_, units)| units);
+    let forms = forms(units, width)
+        .or_else(|| forms(units, Width::Short))
+        .or_else(|| forms(EN, Width::Short))
+        .unwrap_or(&[]);
+    forms
+        .iter()
+        .find(|(candidate, _)| *candidate == category)
+        .or_else(|| forms.iter().find(|(candidate, _)| *candidate == "other"))
+        .map_or_else(|| number.to_string(), |(_, form)| form.replace("{0}", number))
+}
@@ -0,0 +1,84 @@
+// Config validation - misconfiguration caught when an instance is created
+//
+// The checks only warn: an instance still works with a default locale that
+// is not listed or a fallback to a locale nothing loads, it just never finds
+// those translations. getConfigWarnings returns what was found; a strict
+// config turns any warning into an InvalidConfig error instead.
+use serde::Serialize;
+
+use i18n_core::is_well_formed_locale;
+
+use super::{canonicalize_locale, Config, FallbackLocales};
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ConfigWarning {
+    /// "INVALID_LOCALE_TAG", "DEFAULT_LOCALE_NOT_LISTED" or "UNKNOWN_FALLBACK"
+    pub code: &'static str,
+    /// Where in the config: "locales", "default_locale",
+    /// "fallbacks.pt-BR" or "namespace_fallbacks.legal.fr-CA"
+    pub field: String,
+    /// The locale tag as written
+    pub locale: String,
+    pub message: String,
+}
+
+/// Every problem with a config, in the order of its fields
+pub(crate) fn validate(config: &Config) -> Vec<ConfigWarning> {
+    let mut warnings = Vec::new();
+    let listed: Vec<String> = config.locales.iter().map(|locale| canonicalize_locale(locale)).collect();
+
+    for locale in &config.locales {
+        check_tag(&mut warnings, "locales", locale);
+    }
+    if check_tag(&mut warnings, "default_locale", &config.default_locale)
+        && !listed.contains(&canonicalize_locale(&config.default_locale))
+    {
+        warnings.push(ConfigWarning {
+            code: "DEFAULT_LOCALE_NOT_LISTED",
+            field: "default_locale".to_string(),
+            locale: config.default_locale.clone(),
+            message: format!("default_locale {} is not in locales", config.default_locale),
+        });
+    }
+    check_fallbacks(&mut warnings, "fallbacks", &config.fallbacks, &listed);
+    let mut namespaces: Vec<&String> = config.namespace_fallbacks.keys().collect();
+    namespaces.sort();
+    for namespace in namespaces {
+        let field = format!("namespace_fallbacks.{}", namespace);
+        check_fallbacks(&mut warnings, &field, &config.namespace_fallbacks[namespace], &listed);
+    }
+    warnings
+}
+
+/// Warn about a malformed tag; returns whether it is well-formed
+fn check_tag(warnings: &mut Vec<ConfigWarning>, field: &str, locale: &str) -> bool {
+    if is_well_formed_locale(locale) {
+        return true;
+    }
+    warnings.push(ConfigWarning {
+        code: "INVALID_LOCALE_TAG",
+        field: field.to_string(),
+        locale: locale.to_string(),
+        message: format!("{} is not a well-formed BCP 47 locale tag", locale),
+    });
+    false
+}
+
+fn check_fallbacks(warnings: &mut Vec<ConfigWarning>, field: &str, fallbacks: &FallbackLocales, listed: &[String]) {
+    let mut locales: Vec<&String> = fallbacks.keys().collect();
+    locales.sort();
+    for locale in locales {
+        let field = format!("{}.{}", field, locale);
+        check_tag(warnings, &field, locale);
+        for target in &fallbacks[locale] {
+            if check_tag(warnings, &field, target) && !listed.contains(&canonicalize_locale(target)) {
+                warnings.push(ConfigWarning {
+                    code: "UNKNOWN_FALLBACK",
+                    field: field.clone(),
+                    locale: target.clone(),
+                    message: format!("{} falls back to {}, which is not in locales", locale, target),
+                });
+            }
+        }
+    }
+}
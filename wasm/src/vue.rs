@@ -0,0 +1,33 @@
+// vue-i18n pipe pluralization - "no apples | one apple | {count} apples"
+//
+// Choices follow vue-i18n's default choice index: with two choices they are
+// singular | plural; with three or more the first is for exactly 0 and the
+// rest take the locale's plural categories in order (as with a custom
+// pluralRules function returning 1, 2, ... for one, few, ...). The {count}
+// and {n} placeholders become the %d count placeholder.
+use super::{Catalog, ExactValue, PluralCategory, PluralForms};
+
+/// Turn every message containing '|' into a plural block
+pub(crate) fn fold(catalog: &mut Catalog, categories: &[PluralCategory]) {
+    let piped: Vec<String> = catalog
+        .strings
+        .iter()
+        .filter(|(_, text)| text.contains('|'))
+        .map(|(key, _)| key.clone())
+        .collect();
+
+    for key in piped {
+        let text = catalog.strings.remove(&key).unwrap_or_default();
+        let mut choices: Vec<String> = text
+            .split('|')
+            .map(|choice| choice.trim().replace("{count}", "%d").replace("{n}", "%d"))
+            .collect();
+
+        let zero = (choices.len() > 2).then(|| choices.remove(0));
+        let mut forms = PluralForms::from_indexed(categories, choices);
+        if let Some(zero) = zero {
+            forms.exact.push((ExactValue::parse("0").unwrap(), zero));
+        }
+        catalog.plurals.insert(key, forms);
+    }
+}
@@ -0,0 +1,225 @@
+// Minimal XML reader for the XML-based catalog importers
+//
+// Builds an element tree; handles comments, processing instructions, DOCTYPE,
+// CDATA and the predefined and numeric entities. No namespaces or DTD
+// validation - prefixed names are kept as written ("xml:lang").
+
+/// An element with its attributes and child nodes
+#[derive(Debug, Default)]
+pub(crate) struct Element {
+    pub name: String,
+    pub attributes: Vec<(String, String)>,
+    pub children: Vec<Node>,
+}
+
+#[derive(Debug)]
+pub(crate) enum Node {
+    Element(Element),
+    Text(String),
+}
+
+impl Element {
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Child elements, skipping text
+    pub fn elements(&self) -> impl Iterator<Item = &Element> {
+        self.children.iter().filter_map(|node| match node {
+            Node::Element(element) => Some(element),
+            Node::Text(_) => None,
+        })
+    }
+
+    pub fn child(&self, name: &str) -> Option<&Element> {
+        self.elements().find(|element| element.name == name)
+    }
+
+    /// Concatenated text of this element and its descendants
+    pub fn text(&self) -> String {
+        let mut text = String::new();
+        self.collect_text(&mut text);
+        text
+    }
+
+    fn collect_text(&self, text: &mut String) {
+        for node in &self.children {
+            match node {
+                Node::Text(content) => text.push_str(content),
+                Node::Element(element) => element.collect_text(text),
+            }
+        }
+    }
+}
+
+/// Parse a document and return its root element
+pub(crate) fn parse(text: &str) -> Result<Element, String> {
+    let mut stack: Vec<Element> = vec![Element::default()];
+    let mut rest = text.strip_prefix('\u{feff}').unwrap_or(text);
+    let offset = |rest: &str| text.len() - rest.len();
+
+    while !rest.is_empty() {
+        let Some(open) = rest.find('<') else {
+            push_text(&mut stack, &decode_entities(rest)?);
+            break;
+        };
+        if open > 0 {
+            push_text(&mut stack, &decode_entities(&rest[..open])?);
+            rest = &rest[open..];
+        }
+
+        let skip_past = |rest: &str, end: &str| -> Result<usize, String> {
+            rest.find(end)
+                .map(|index| index + end.len())
+                .ok_or_else(|| format!("unterminated markup at byte {}", offset(rest)))
+        };
+
+        if rest.starts_with("<!--") {
+            rest = &rest[skip_past(rest, "-->")?..];
+        } else if rest.starts_with("<?") {
+            rest = &rest[skip_past(rest, "?>")?..];
+        } else if let Some(after) = rest.strip_prefix("<![CDATA[") {
+            let end = after
+                .find("]]>")
+                .ok_or_else(|| format!("unterminated CDATA at byte {}", offset(rest)))?;
+            push_text(&mut stack, &after[..end]);
+            rest = &after[end + 3..];
+        } else if rest.starts_with("<!") {
+            // DOCTYPE, possibly with an internal subset in [...]
+            let end = match (rest.find('['), rest.find('>')) {
+                (Some(bracket), Some(close)) if bracket < close => skip_past(rest, "]>")?,
+                _ => skip_past(rest, ">")?,
+            };
+            rest = &rest[end..];
+        } else if let Some(after) = rest.strip_prefix("</") {
+            let end = skip_past(after, ">")?;
+            let name = after[..end - 1].trim();
+            let element = stack.pop().filter(|_| !stack.is_empty());
+            match element {
+                Some(element) if element.name == name => {
+                    stack.last_mut().unwrap().children.push(Node::Element(element));
+                }
+                _ => return Err(format!("unexpected </{}> at byte {}", name, offset(rest))),
+            }
+            rest = &after[end..];
+        } else {
+            let end = tag_end(rest).ok_or_else(|| format!("unterminated tag at byte {}", offset(rest)))?;
+            let self_closing = rest[..end].ends_with('/');
+            let tag = rest[1..end].trim_end_matches('/');
+            let element = parse_tag(tag).map_err(|e| format!("{} at byte {}", e, offset(rest)))?;
+            if self_closing {
+                stack.last_mut().unwrap().children.push(Node::Element(element));
+            } else {
+                stack.push(element);
+            }
+            rest = &rest[end + 1..];
+        }
+    }
+
+    if stack.len() != 1 {
+        return Err(format!("unclosed <{}>", stack.last().unwrap().name));
+    }
+    stack
+        .pop()
+        .unwrap()
+        .children
+        .into_iter()
+        .find_map(|node| match node {
+            Node::Element(element) => Some(element),
+            Node::Text(_) => None,
+        })
+        .ok_or_else(|| "no root element".to_string())
+}
+
+fn push_text(stack: &mut [Element], text: &str) {
+    let children = &mut stack.last_mut().unwrap().children;
+    match children.last_mut() {
+        Some(Node::Text(existing)) => existing.push_str(text),
+        _ => children.push(Node::Text(text.to_string())),
+    }
+}
+
+/// Index of the '>' closing a start tag, skipping quoted attribute values
+fn tag_end(text: &str) -> Option<usize> {
+    let mut quote = None;
+    for (index, c) in text.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(open), _) if c == open => quote = None,
+            (None, '>') => return Some(index),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Parse `name attr="value" other='value'`
+fn parse_tag(tag: &str) -> Result<Element, String> {
+    let tag = tag.trim();
+    let name_end = tag.find(char::is_whitespace).unwrap_or(tag.len());
+    let mut element = Element {
+        name: tag[..name_end].to_string(),
+        ..Element::default()
+    };
+    if element.name.is_empty() {
+        return Err("empty tag name".to_string());
+    }
+
+    let mut rest = tag[name_end..].trim_start();
+    while !rest.is_empty() {
+        let (name, after) = rest
+            .split_once('=')
+            .ok_or_else(|| format!("malformed attribute in <{}>", element.name))?;
+        let after = after.trim_start();
+        let quote = after
+            .chars()
+            .next()
+            .filter(|c| *c == '"' || *c == '\'')
+            .ok_or_else(|| format!("unquoted attribute in <{}>", element.name))?;
+        let end = after[1..]
+            .find(quote)
+            .ok_or_else(|| format!("unterminated attribute in <{}>", element.name))?;
+        let value = decode_entities(&after[1..end + 1])?;
+        element.attributes.push((name.trim().to_string(), value));
+        rest = after[end + 2..].trim_start();
+    }
+    Ok(element)
+}
+
+pub(crate) fn decode_entities(text: &str) -> Result<String, String> {
+    if !text.contains('&') {
+        return Ok(text.to_string());
+    }
+
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        result.push_str(&rest[..amp]);
+        let semicolon = rest[amp..]
+            .find(';')
+            .ok_or_else(|| "unterminated entity".to_string())?;
+        let entity = &rest[amp + 1..amp + semicolon];
+        let decoded = match entity {
+            "lt" => '<',
+            "gt" => '>',
+            "amp" => '&',
+            "quot" => '"',
+            "apos" => '\'',
+            _ => {
+                let code = match entity.strip_prefix("#x").or_else(|| entity.strip_prefix("#X")) {
+                    Some(hex) => u32::from_str_radix(hex, 16).ok(),
+                    None => entity.strip_prefix('#').and_then(|dec| dec.parse().ok()),
+                };
+                code.and_then(char::from_u32)
+                    .ok_or_else(|| format!("unknown entity &{};", entity))?
+            }
+        };
+        result.push(decoded);
+        rest = &rest[amp + semicolon + 1..];
+    }
+    result.push_str(rest);
+    Ok(result)
+}